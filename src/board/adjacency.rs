@@ -0,0 +1,154 @@
+use bevy::utils::HashMap;
+
+use super::{
+    axial::Axial,
+    board_data::BoardData,
+    edge::{Edge, EdgeCoord},
+    hex::{BuildType, Hex},
+    vertex::{Vertex, VertexCoord},
+};
+
+/// Which parts of the board touch which, worked out once when the board is made since the
+/// shape of the board never changes after that. Only things on the board are listed
+#[derive(Debug, Default)]
+pub struct Adjacency {
+    hex_vertices: HashMap<Axial, [VertexCoord; 6]>,
+    vertex_hexes: HashMap<VertexCoord, Vec<Axial>>,
+    vertex_edges: HashMap<VertexCoord, Vec<EdgeCoord>>,
+    edge_vertices: HashMap<EdgeCoord, [VertexCoord; 2]>,
+}
+impl Adjacency {
+    pub fn new(
+        hexes: &HashMap<Axial, Hex>,
+        vertices: &HashMap<VertexCoord, Vertex>,
+        edges: &HashMap<EdgeCoord, Edge>,
+    ) -> Self {
+        let mut adjacency = Adjacency::default();
+        for &pos in hexes.keys() {
+            adjacency
+                .hex_vertices
+                .insert(pos, VertexCoord::corners_of(pos));
+        }
+        for &v in vertices.keys() {
+            let on_board = |h: &Axial| hexes.contains_key(h);
+            adjacency
+                .vertex_hexes
+                .insert(v, v.hexes().into_iter().filter(on_board).collect());
+            let on_board = |e: &EdgeCoord| edges.contains_key(e);
+            adjacency
+                .vertex_edges
+                .insert(v, v.edges().into_iter().filter(on_board).collect());
+        }
+        for &coords in edges.keys() {
+            let (a, b) = coords.ends();
+            adjacency.edge_vertices.insert(coords, [a, b]);
+        }
+        adjacency
+    }
+}
+
+impl BoardData {
+    pub fn hexes_of_vertex(&self, v: VertexCoord) -> &[Axial] {
+        self.adjacency
+            .vertex_hexes
+            .get(&v)
+            .map(|h| h.as_slice())
+            .unwrap_or_default()
+    }
+    pub fn vertices_of_hex(&self, pos: Axial) -> &[VertexCoord] {
+        self.adjacency
+            .hex_vertices
+            .get(&pos)
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+    }
+    pub fn edges_of_vertex(&self, v: VertexCoord) -> &[EdgeCoord] {
+        self.adjacency
+            .vertex_edges
+            .get(&v)
+            .map(|e| e.as_slice())
+            .unwrap_or_default()
+    }
+    pub fn vertices_of_edge(&self, coords: EdgeCoord) -> &[VertexCoord] {
+        self.adjacency
+            .edge_vertices
+            .get(&coords)
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Settlements and cities on the corners of a hex
+    pub fn buildings_on_hex(&self, pos: Axial) -> impl Iterator<Item = &Vertex> {
+        self.vertices_of_hex(pos)
+            .iter()
+            .filter_map(|v| self.vertices.get(v))
+            .filter(|v| v.build_type != BuildType::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::generate::{GenerateOptions, BOARD_SEED},
+        player::PlayerId,
+    };
+
+    fn board() -> BoardData {
+        BoardData::generate(BOARD_SEED, GenerateOptions::default())
+    }
+
+    #[test]
+    fn hex_corners_match_topology() {
+        let board = board();
+        for &pos in board.hexes.keys() {
+            assert_eq!(board.vertices_of_hex(pos), VertexCoord::corners_of(pos));
+        }
+        //off the board there is nothing listed
+        let far = Axial::new(30, 30);
+        assert!(board.vertices_of_hex(far).is_empty());
+    }
+
+    #[test]
+    fn edge_ends_match_topology() {
+        let board = board();
+        for &coords in board.edges.keys() {
+            let (a, b) = coords.ends();
+            assert_eq!(board.vertices_of_edge(coords), [a, b]);
+        }
+    }
+
+    #[test]
+    fn buildings_on_hex_are_its_corners() {
+        let mut board = board();
+        let pos = *board.hexes.keys().next().unwrap();
+        let corners = VertexCoord::corners_of(pos);
+        let elsewhere = *board
+            .vertices
+            .keys()
+            .find(|v| !corners.contains(v))
+            .unwrap();
+        for (v, build_type, owner) in [
+            (corners[0], BuildType::Settlement, PlayerId(0)),
+            (corners[3], BuildType::City, PlayerId(1)),
+            (elsewhere, BuildType::Settlement, PlayerId(2)),
+        ] {
+            let vertex = board.vertices.get_mut(&v).unwrap();
+            vertex.build_type = build_type;
+            vertex.owner = Some(owner);
+        }
+
+        let mut found: Vec<(VertexCoord, BuildType)> = board
+            .buildings_on_hex(pos)
+            .map(|v| (v.pos, v.build_type))
+            .collect();
+        found.sort_by_key(|&(v, _)| corners.iter().position(|&c| c == v));
+        assert_eq!(
+            found,
+            vec![
+                (corners[0], BuildType::Settlement),
+                (corners[3], BuildType::City)
+            ]
+        );
+    }
+}
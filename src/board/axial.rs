@@ -1,6 +1,12 @@
-use std::{cmp::Ordering, ops::Add};
+use std::{
+    cmp::Ordering,
+    ops::{Add, Mul, Neg, Sub},
+};
 
 use bevy::prelude::{Vec2, Vec3};
+
+const SQRT_3: f32 = 1.732_050_8;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Axial {
     q: i32,
@@ -8,38 +14,220 @@ pub struct Axial {
 }
 
 impl Axial {
-    pub fn new(q: i32, r: i32) -> Self {
+    //unit steps in order, each one 60 degrees clockwise from the last on screen
+    pub const DIRECTIONS: [Axial; 6] = [
+        Axial::new(1, 0),
+        Axial::new(0, 1),
+        Axial::new(-1, 1),
+        Axial::new(-1, 0),
+        Axial::new(0, -1),
+        Axial::new(1, -1),
+    ];
+
+    pub const fn new(q: i32, r: i32) -> Self {
         Axial { q, r }
     }
+    pub fn q(&self) -> i32 {
+        self.q
+    }
+    pub fn r(&self) -> i32 {
+        self.r
+    }
+    //the third cube coordinate, q + r + s is always 0
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+    pub fn to_cube(self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Self {
+        debug_assert_eq!(q + r + s, 0, "cube coordinates have to sum to 0");
+        Axial::new(q, r)
+    }
 
     pub fn get_cartesian(&self) -> Vec3 {
-        let scale_x = 60.;
-        let scale_y = 60.;
-        let l = Vec2::new(
-            f32::cos(std::f32::consts::PI / 6.0),
-            f32::sin(std::f32::consts::PI / 6.0),
-        );
+        LAYOUT.to_pixel(*self).extend(0.)
+    }
+
+    //hex centers are every third point of the lattice, everything else is a corner
+    pub fn is_hex_center(&self) -> bool {
+        (self.q - self.r).rem_euclid(3) == 0
+    }
 
-        // Line 1
-        let a2: Vec2 = l + Vec2::new(-l.y, l.x) * self.q as f32;
+    //the lattice is three times finer than the hexes, so the toolkit below walks corners. to go
+    //from hex to hex, like map files count them, work in hex steps and convert at the ends
+    pub fn from_hex_steps(steps: Axial) -> Axial {
+        Axial::new(-1, 2) * steps.q + Axial::new(-2, 1) * steps.r
+    }
+    //None for points that aren't hex centers
+    pub fn to_hex_steps(self) -> Option<Axial> {
+        if !self.is_hex_center() {
+            return None;
+        }
+        Some(Axial::new(
+            (self.q + 2 * self.r) / 3,
+            (-2 * self.q - self.r) / 3,
+        ))
+    }
 
-        let m = f32::tan(std::f32::consts::PI / 6.0);
-        let y = m * (self.r as f32 - a2.x) + a2.y;
+    pub fn length(&self) -> i32 {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+    pub fn distance(&self, other: Axial) -> i32 {
+        (*self - other).length()
+    }
+    pub fn neighbors(&self) -> [Axial; 6] {
+        Axial::DIRECTIONS.map(|d| *self + d)
+    }
 
-        // scale is the size of the hex from the center to the edge
-        Vec3::new(self.r as f32 * scale_x, y * scale_y, 0.)
+    //every point exactly radius steps away, walking clockwise
+    pub fn ring(&self, radius: i32) -> Vec<Axial> {
+        if radius <= 0 {
+            return vec![*self];
+        }
+        let mut ring = Vec::with_capacity(6 * radius as usize);
+        let mut a = *self + Axial::DIRECTIONS[4] * radius;
+        for d in Axial::DIRECTIONS {
+            for _ in 0..radius {
+                ring.push(a);
+                a = a + d;
+            }
+        }
+        ring
+    }
+    //every point within radius, from the center outwards one ring at a time
+    pub fn spiral(&self, radius: i32) -> Vec<Axial> {
+        let mut spiral = vec![*self];
+        for k in 1..=radius {
+            spiral.extend(self.ring(k));
+        }
+        spiral
+    }
+    //every point within radius, in no particular order
+    pub fn range(&self, radius: i32) -> Vec<Axial> {
+        self.range_intersection(radius, *self, radius)
     }
+    //the points within radius of self and within other_radius of other
+    pub fn range_intersection(&self, radius: i32, other: Axial, other_radius: i32) -> Vec<Axial> {
+        let q_min = (self.q - radius).max(other.q - other_radius);
+        let q_max = (self.q + radius).min(other.q + other_radius);
+        let r_min = (self.r - radius).max(other.r - other_radius);
+        let r_max = (self.r + radius).min(other.r + other_radius);
+        let s_min = (self.s() - radius).max(other.s() - other_radius);
+        let s_max = (self.s() + radius).min(other.s() + other_radius);
+        let mut points = Vec::new();
+        for q in q_min..=q_max {
+            for r in r_min.max(-q - s_max)..=r_max.min(-q - s_min) {
+                points.push(Axial::new(q, r));
+            }
+        }
+        points
+    }
+
+    //the points a straight line from self to other passes through, both ends included
+    pub fn line_to(&self, other: Axial) -> Vec<Axial> {
+        let n = self.distance(other);
+        if n == 0 {
+            return vec![*self];
+        }
+        //nudged so lines running along cell edges always round the same way
+        let (aq, ar) = (self.q as f32 + 1e-6, self.r as f32 + 1e-6);
+        let (bq, br) = (other.q as f32 + 1e-6, other.r as f32 + 1e-6);
+        (0..=n)
+            .map(|i| {
+                let t = i as f32 / n as f32;
+                cube_round(aq + (bq - aq) * t, ar + (br - ar) * t)
+            })
+            .collect()
+    }
+
+    //turn around center by 60 degree steps, positive is counterclockwise on screen
+    pub fn rotate(&self, center: Axial, steps: i32) -> Axial {
+        let (mut q, mut r, mut s) = (*self - center).to_cube();
+        for _ in 0..steps.rem_euclid(6) {
+            (q, r, s) = (-s, -q, -r);
+        }
+        center + Axial::from_cube(q, r, s)
+    }
+    //mirror across the line through center where q stays the same
+    pub fn reflect_q(&self, center: Axial) -> Axial {
+        let (q, r, s) = (*self - center).to_cube();
+        center + Axial::from_cube(q, s, r)
+    }
+    //mirror across the line through center where r stays the same
+    pub fn reflect_r(&self, center: Axial) -> Axial {
+        let (q, r, s) = (*self - center).to_cube();
+        center + Axial::from_cube(s, r, q)
+    }
+    //mirror across the line through center where s stays the same
+    pub fn reflect_s(&self, center: Axial) -> Axial {
+        let (q, r, s) = (*self - center).to_cube();
+        center + Axial::from_cube(r, q, s)
+    }
+}
+
+//forward (f) and inverse (b) matrices between axial steps and pixels, in units of size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orientation {
+    f: [f32; 4],
+    b: [f32; 4],
+}
+impl Orientation {
+    //flat topped cells of the fine lattice with r running along x. the board hexes are pointy
+    //topped, each one is the six cells around its center with corners to the north and south
+    pub const FLAT: Orientation = Orientation {
+        f: [0., 1.5, SQRT_3, SQRT_3 / 2.],
+        b: [-1. / 3., 1. / SQRT_3, 2. / 3., 0.],
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub orientation: Orientation,
+    //distance from a lattice point to the corners of its cell
+    pub size: Vec2,
+    //where Axial(0, 0) sits
+    pub origin: Vec2,
+}
+impl Layout {
+    pub fn to_pixel(self, a: Axial) -> Vec2 {
+        let f = self.orientation.f;
+        let (q, r) = (a.q as f32, a.r as f32);
+        let x = (f[0] * q + f[1] * r) * self.size.x;
+        let y = (f[2] * q + f[3] * r) * self.size.y;
+        Vec2::new(x, y) + self.origin
+    }
+
+    //the inverse of to_pixel, named as its pair rather than as a constructor
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_pixel(&self, p: Vec2) -> Axial {
+        let b = self.orientation.b;
+        let p = (p - self.origin) / self.size;
+        let q = b[0] * p.x + b[1] * p.y;
+        let r = b[2] * p.x + b[3] * p.y;
+        cube_round(q, r)
+    }
+}
 
-    pub fn get_axial(world_pos: Vec3) -> Self {
-        let result = Vec2::new(world_pos.x / 0.5, world_pos.z / 0.5);
-        let m = f32::tan(std::f32::consts::PI / 6.0);
-        let l = Vec2::new(
-            f32::cos(std::f32::consts::PI / 6.0),
-            f32::sin(std::f32::consts::PI / 6.0),
-        );
-        let cx = (result.y - l.y - m * result.x + m * l.x) / (-l.y * m - l.x * m);
-        Axial::new(cx as i32, result.x as i32)
+//one step of the lattice is 60 pixels along x, hexes and vertices both sit on it
+pub const LAYOUT: Layout = Layout {
+    orientation: Orientation::FLAT,
+    size: Vec2::new(40., 40.),
+    origin: Vec2::ZERO,
+};
+
+//round in cube space so the point lands in the cell it's actually in,
+//the component that moved the most is rebuilt from the other two
+fn cube_round(q: f32, r: f32) -> Axial {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
     }
+    Axial::new(rq as i32, rr as i32)
 }
 impl Add<Axial> for Axial {
     type Output = Axial;
@@ -48,6 +236,27 @@ impl Add<Axial> for Axial {
         Axial::new(self.q + other.q, self.r + other.r)
     }
 }
+impl Sub<Axial> for Axial {
+    type Output = Axial;
+
+    fn sub(self, other: Axial) -> Axial {
+        Axial::new(self.q - other.q, self.r - other.r)
+    }
+}
+impl Neg for Axial {
+    type Output = Axial;
+
+    fn neg(self) -> Axial {
+        Axial::new(-self.q, -self.r)
+    }
+}
+impl Mul<i32> for Axial {
+    type Output = Axial;
+
+    fn mul(self, k: i32) -> Axial {
+        Axial::new(self.q * k, self.r * k)
+    }
+}
 impl PartialOrd for Axial {
     fn partial_cmp(&self, other: &Axial) -> Option<Ordering> {
         if self.q < other.q || (self.q == other.q && self.r < other.r) {
@@ -65,3 +274,130 @@ impl Ord for Axial {
         self.partial_cmp(other).unwrap() // Unwrap the Option to get the Ordering
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashSet;
+
+    use super::*;
+    use crate::board::board_data::HEX_OFFSETS;
+
+    fn centers() -> Vec<Axial> {
+        Axial::new(0, 0).range(3)
+    }
+
+    #[test]
+    fn ring_size_and_distance() {
+        for c in centers() {
+            for r in 1..6 {
+                let ring = c.ring(r);
+                assert_eq!(ring.len(), 6 * r as usize);
+                assert!(ring.iter().all(|p| p.distance(c) == r));
+            }
+        }
+    }
+
+    #[test]
+    fn spiral_covers_range() {
+        for c in centers() {
+            for r in 0..6 {
+                let spiral = c.spiral(r);
+                let range = c.range(r);
+                let size = (3 * r * (r + 1) + 1) as usize;
+                assert_eq!(spiral.len(), size);
+                assert_eq!(range.len(), size);
+                let spiral: HashSet<Axial> = spiral.into_iter().collect();
+                let range: HashSet<Axial> = range.into_iter().collect();
+                assert_eq!(spiral, range);
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_and_reflect() {
+        for c in centers() {
+            for p in c.range(4) {
+                assert_eq!(p.rotate(c, 6), p);
+                assert_eq!(p.rotate(c, 1).distance(c), p.distance(c));
+                assert_eq!(p.rotate(c, 1).rotate(c, -1), p);
+                assert_eq!(p.reflect_q(c).reflect_q(c), p);
+                assert_eq!(p.reflect_r(c).reflect_r(c), p);
+                assert_eq!(p.reflect_s(c).reflect_s(c), p);
+            }
+        }
+    }
+
+    #[test]
+    fn line_steps() {
+        for c in centers() {
+            for p in c.range(5) {
+                let line = c.line_to(p);
+                assert_eq!(line.len(), c.distance(p) as usize + 1);
+                assert_eq!(line.first(), Some(&c));
+                assert_eq!(line.last(), Some(&p));
+                assert!(line.windows(2).all(|w| w[0].distance(w[1]) == 1));
+            }
+        }
+    }
+
+    #[test]
+    fn range_intersection_matches_filter() {
+        for a in centers() {
+            for b in centers() {
+                for (ra, rb) in [(0, 2), (2, 2), (3, 1), (4, 3)] {
+                    let mut got = a.range_intersection(ra, b, rb);
+                    let mut want: Vec<Axial> = a
+                        .range(ra)
+                        .into_iter()
+                        .filter(|p| p.distance(b) <= rb)
+                        .collect();
+                    got.sort();
+                    want.sort();
+                    assert_eq!(got, want);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cube_coordinates() {
+        for p in centers() {
+            let (q, r, s) = p.to_cube();
+            assert_eq!((q, r), (p.q(), p.r()));
+            assert_eq!(q + r + s, 0);
+            assert_eq!(Axial::from_cube(q, r, s), p);
+            assert_eq!(p.length(), p.distance(Axial::new(0, 0)));
+        }
+    }
+
+    #[test]
+    fn pixel_round_trip() {
+        //wide enough to take in every hex and corner of the standard board
+        for a in Axial::new(0, 0).range(10) {
+            assert_eq!(LAYOUT.from_pixel(LAYOUT.to_pixel(a)), a);
+        }
+    }
+
+    #[test]
+    fn hex_steps() {
+        let center = Axial::new(0, 0);
+        for a in center.range(9).into_iter().filter(|a| a.is_hex_center()) {
+            let steps = a.to_hex_steps().unwrap();
+            assert_eq!(Axial::from_hex_steps(steps), a);
+            //neighbouring hexes are one step away, the lattice neighbours are corners
+            let around: HashSet<Axial> = steps
+                .neighbors()
+                .into_iter()
+                .map(Axial::from_hex_steps)
+                .collect();
+            let expected: HashSet<Axial> = HEX_OFFSETS.iter().map(|&o| a + o).collect();
+            assert_eq!(around, expected);
+            assert!(a.neighbors().iter().all(|c| c.to_hex_steps().is_none()));
+        }
+        //hexes two out are all two steps away
+        let origin = center.to_hex_steps().unwrap();
+        for a in [Axial::new(0, 3), Axial::new(2, 2)] {
+            assert_eq!(origin.distance(a.to_hex_steps().unwrap()), 2);
+        }
+    }
+}
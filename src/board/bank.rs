@@ -0,0 +1,329 @@
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    board_data::BoardData,
+    hex::{self, resource_tint, RESOURCES},
+    roll::{TurnAction, TurnEnded},
+};
+use crate::player::{PlayerId, Players};
+
+//cards of each resource in the game, everything not in a player's hand is in the bank
+pub const BANK_SIZE: u32 = 19;
+
+#[derive(Resource, Debug)]
+pub struct Bank {
+    pub stock: HashMap<hex::Resource, u32>,
+}
+impl Default for Bank {
+    fn default() -> Self {
+        Bank {
+            stock: RESOURCES.iter().map(|&r| (r, BANK_SIZE)).collect(),
+        }
+    }
+}
+impl Bank {
+    pub fn count(&self, resource: hex::Resource) -> u32 {
+        *self.stock.get(&resource).unwrap_or(&0)
+    }
+    //hands out as many as are left, up to amount
+    pub fn take(&mut self, resource: hex::Resource, amount: u32) -> u32 {
+        let held = self.stock.entry(resource).or_default();
+        let taken = amount.min(*held);
+        *held -= taken;
+        taken
+    }
+    pub fn put_all(&mut self, cards: &[(hex::Resource, u32)]) {
+        for &(resource, amount) in cards {
+            *self.stock.entry(resource).or_default() += amount;
+        }
+    }
+
+    /// Settle a dice roll against what is left. A resource the bank can't cover for everyone is
+    /// paid to nobody, unless only one player is owed it, then they get whatever is left
+    pub fn pay_out(
+        &mut self,
+        payouts: Vec<(PlayerId, hex::Resource, u32)>,
+    ) -> Vec<(PlayerId, hex::Resource, u32)> {
+        let mut paid = Vec::new();
+        for resource in RESOURCES {
+            let owed: Vec<(PlayerId, u32)> = payouts
+                .iter()
+                .filter(|p| p.1 == resource)
+                .map(|p| (p.0, p.2))
+                .collect();
+            let total: u32 = owed.iter().map(|o| o.1).sum();
+            let mut owners: Vec<PlayerId> = owed.iter().map(|o| o.0).collect();
+            owners.sort();
+            owners.dedup();
+
+            if total <= self.count(resource) {
+                for (owner, amount) in owed {
+                    paid.push((owner, resource, self.take(resource, amount)));
+                }
+            } else if owners.len() == 1 {
+                paid.push((owners[0], resource, self.take(resource, total)));
+            } else {
+                info!("the bank is out of {:?}", resource);
+            }
+        }
+        paid
+    }
+}
+
+//what the active player has picked so far in the trade dialog
+#[derive(Resource, Default)]
+pub struct BankTrade {
+    give: Option<hex::Resource>,
+    get: Option<hex::Resource>,
+}
+
+#[derive(Component)]
+pub struct TradeDialog;
+#[derive(Component)]
+pub struct GiveChoice(pub hex::Resource);
+#[derive(Component)]
+pub struct GetChoice(pub hex::Resource);
+#[derive(Component)]
+pub struct ConfirmTrade;
+#[derive(Component)]
+pub struct CloseTrade;
+
+pub fn open_bank_trade(
+    mut cmd: Commands,
+    q: Query<&ActionState<TurnAction>>,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    q_dialog: Query<(), With<TradeDialog>>,
+) {
+    if !q.single().just_pressed(TurnAction::Trade) || !q_dialog.is_empty() {
+        return;
+    }
+    cmd.init_resource::<BankTrade>();
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(24.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+            ..default()
+        },
+        TradeDialog,
+    ))
+    .with_children(|parent| {
+        let row = NodeBundle {
+            style: Style {
+                column_gap: Val::Px(8.),
+                ..default()
+            },
+            ..default()
+        };
+        parent.spawn(row.clone()).with_children(|row| {
+            for resource in RESOURCES {
+                let ratio = board.trade_ratio(players.active, resource);
+                spawn_button(
+                    row,
+                    format!("Give {} {:?}", ratio, resource),
+                    resource_tint(resource),
+                    GiveChoice(resource),
+                );
+            }
+        });
+        parent.spawn(row.clone()).with_children(|row| {
+            for resource in RESOURCES {
+                spawn_button(
+                    row,
+                    format!("Get 1 {:?}", resource),
+                    resource_tint(resource),
+                    GetChoice(resource),
+                );
+            }
+        });
+        parent.spawn(row).with_children(|row| {
+            spawn_button(
+                row,
+                "Trade".to_string(),
+                Color::rgb(0.4, 0.7, 0.4),
+                ConfirmTrade,
+            );
+            spawn_button(
+                row,
+                "Close".to_string(),
+                Color::rgb(0.7, 0.4, 0.4),
+                CloseTrade,
+            );
+        });
+    });
+}
+
+fn spawn_button(parent: &mut ChildBuilder, label: String, color: Color, marker: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    padding: UiRect::all(Val::Px(8.)),
+                    border: UiRect::all(Val::Px(3.)),
+                    ..default()
+                },
+                background_color: color.into(),
+                border_color: color.into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 18.,
+                    color: Color::BLACK,
+                    ..default()
+                },
+            ));
+        });
+}
+
+pub fn bank_trade(
+    mut cmd: Commands,
+    board: Res<BoardData>,
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
+    mut trade: ResMut<BankTrade>,
+    q_give: Query<(&Interaction, &GiveChoice), Changed<Interaction>>,
+    q_get: Query<(&Interaction, &GetChoice), Changed<Interaction>>,
+    q_confirm: Query<&Interaction, (Changed<Interaction>, With<ConfirmTrade>)>,
+    q_close: Query<&Interaction, (Changed<Interaction>, With<CloseTrade>)>,
+    q_dialog: Query<Entity, With<TradeDialog>>,
+    mut q_borders: Query<(&mut BorderColor, Option<&GiveChoice>, Option<&GetChoice>)>,
+) {
+    for (interaction, choice) in q_give.iter() {
+        if *interaction == Interaction::Pressed {
+            trade.give = Some(choice.0);
+        }
+    }
+    for (interaction, choice) in q_get.iter() {
+        if *interaction == Interaction::Pressed {
+            trade.get = Some(choice.0);
+        }
+    }
+    //outline whatever is picked
+    if trade.is_changed() {
+        for (mut border, give, get) in q_borders.iter_mut() {
+            let picked = give.is_some_and(|g| Some(g.0) == trade.give)
+                || get.is_some_and(|g| Some(g.0) == trade.get);
+            let resource = give.map(|g| g.0).or(get.map(|g| g.0));
+            if let Some(resource) = resource {
+                *border = if picked {
+                    Color::WHITE.into()
+                } else {
+                    resource_tint(resource).into()
+                };
+            }
+        }
+    }
+
+    let confirmed = q_confirm.iter().any(|i| *i == Interaction::Pressed);
+    let closed = q_close.iter().any(|i| *i == Interaction::Pressed);
+    if confirmed {
+        if let (Some(give), Some(get)) = (trade.give, trade.get) {
+            let ratio = board.trade_ratio(players.active, give);
+            if give == get {
+                info!("pick two different resources");
+            } else if !players.active().has_all(&[(give, ratio)]) {
+                info!(
+                    "{} needs {} {:?} to trade",
+                    players.active().name,
+                    ratio,
+                    give
+                );
+            } else if bank.count(get) == 0 {
+                info!("the bank is out of {:?}", get);
+            } else {
+                let player = players.active_mut();
+                player.take_all(&[(give, ratio)]);
+                bank.put_all(&[(give, ratio)]);
+                player.add(get, bank.take(get, 1));
+                info!(
+                    "{} trades {} {:?} for 1 {:?}",
+                    player.name, ratio, give, get
+                );
+            }
+        }
+    }
+    if closed {
+        close_bank_trade(&mut cmd, &q_dialog);
+    }
+}
+
+//the dialog stays open through the robber and closes when the turn is over
+pub fn end_bank_trade(
+    mut cmd: Commands,
+    mut ended: EventReader<TurnEnded>,
+    q_dialog: Query<Entity, With<TradeDialog>>,
+) {
+    if ended.iter().count() > 0 {
+        close_bank_trade(&mut cmd, &q_dialog);
+    }
+}
+
+fn close_bank_trade(cmd: &mut Commands, q_dialog: &Query<Entity, With<TradeDialog>>) {
+    for entity in q_dialog.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+    cmd.remove_resource::<BankTrade>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::hex::Resource::{Brick, Ore, Wood};
+
+    fn low(resource: hex::Resource, left: u32) -> Bank {
+        let mut bank = Bank::default();
+        bank.take(resource, BANK_SIZE - left);
+        bank
+    }
+
+    #[test]
+    fn pays_everyone_when_it_can() {
+        let mut bank = Bank::default();
+        let paid = bank.pay_out(vec![
+            (PlayerId(0), Wood, 2),
+            (PlayerId(1), Wood, 1),
+            (PlayerId(1), Ore, 1),
+        ]);
+        assert_eq!(paid.len(), 3);
+        assert!(paid.contains(&(PlayerId(0), Wood, 2)));
+        assert!(paid.contains(&(PlayerId(1), Wood, 1)));
+        assert!(paid.contains(&(PlayerId(1), Ore, 1)));
+        assert_eq!(bank.count(Wood), BANK_SIZE - 3);
+        assert_eq!(bank.count(Ore), BANK_SIZE - 1);
+    }
+
+    #[test]
+    fn shortfall_between_owners_pays_nobody() {
+        let mut bank = low(Wood, 2);
+        let paid = bank.pay_out(vec![
+            (PlayerId(0), Wood, 2),
+            (PlayerId(1), Wood, 1),
+            (PlayerId(2), Brick, 1),
+        ]);
+        //the other resources are still paid
+        assert_eq!(paid, vec![(PlayerId(2), Brick, 1)]);
+        assert_eq!(bank.count(Wood), 2);
+    }
+
+    #[test]
+    fn shortfall_for_one_owner_pays_what_is_left() {
+        let mut bank = low(Wood, 2);
+        //two hexes owed to the same player still count as one owner
+        let paid = bank.pay_out(vec![(PlayerId(0), Wood, 2), (PlayerId(0), Wood, 1)]);
+        assert_eq!(paid, vec![(PlayerId(0), Wood, 2)]);
+        assert_eq!(bank.count(Wood), 0);
+    }
+}
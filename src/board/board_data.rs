@@ -5,56 +5,37 @@ use bevy::{
 use lazy_static::lazy_static;
 
 use super::{
-    axial::Axial,
-    edge::{Edge, EdgeCoords},
+    adjacency::Adjacency,
+    axial::{Axial, LAYOUT},
+    edge::{Edge, EdgeCoord, EdgeType},
+    harbor::{Harbor, HARBORS},
     hex::{self, *},
-    vertex::Vertex,
+    vertex::{Vertex, VertexCoord},
 };
+use crate::player::PlayerId;
 
 lazy_static! {
-    static ref OFFSETS: [Axial; 6] = [
-        Axial::new(1, 0),
-        Axial::new(0, 1),
-        Axial::new(-1, 1),
-        Axial::new(-1, 0),
-        Axial::new(0, -1),
-        Axial::new(1, -1),
+    //hex centers sit three steps apart from each other on the vertex grid
+    pub static ref HEX_OFFSETS: [Axial; 6] = [
+        Axial::new(1, 1),
+        Axial::new(-1, 2),
+        Axial::new(-2, 1),
+        Axial::new(-1, -1),
+        Axial::new(1, -2),
+        Axial::new(2, -1),
     ];
 }
 #[derive(Resource, Debug)]
 pub struct BoardData {
     pub hexes: HashMap<Axial, Hex>,
-    pub edges: HashMap<EdgeCoords, Edge>,
-    pub vertices: HashMap<Axial, Vertex>,
+    pub edges: HashMap<EdgeCoord, Edge>,
+    pub vertices: HashMap<VertexCoord, Vertex>,
+    pub harbors: Vec<Harbor>,
     pub robber: Axial,
+    pub adjacency: Adjacency,
 }
 impl BoardData {
-    pub fn new() -> Self {
-        let mut hex_data: HashMap<Axial, (hex::Resource, i32)> = HashMap::new();
-        hex_data.insert(Axial::new(4, -2), (hex::Resource::Ore, 10));
-        hex_data.insert(Axial::new(3, 0), (hex::Resource::Sheep, 2));
-        hex_data.insert(Axial::new(2, 2), (hex::Resource::Wood, 9));
-
-        hex_data.insert(Axial::new(3, -3), (hex::Resource::Wheat, 12));
-        hex_data.insert(Axial::new(2, -1), (hex::Resource::Brick, 6));
-        hex_data.insert(Axial::new(1, 1), (hex::Resource::Sheep, 4));
-        hex_data.insert(Axial::new(0, 3), (hex::Resource::Brick, 10));
-
-        hex_data.insert(Axial::new(2, -4), (hex::Resource::Wheat, 9));
-        hex_data.insert(Axial::new(1, -2), (hex::Resource::Wood, 11));
-        hex_data.insert(Axial::new(0, 0), (hex::Resource::None, 0));
-        hex_data.insert(Axial::new(-1, 2), (hex::Resource::Wood, 3));
-        hex_data.insert(Axial::new(-2, 4), (hex::Resource::Ore, 8));
-
-        hex_data.insert(Axial::new(0, -3), (hex::Resource::Wood, 8));
-        hex_data.insert(Axial::new(-1, -1), (hex::Resource::Ore, 3));
-        hex_data.insert(Axial::new(-2, 1), (hex::Resource::Wheat, 4));
-        hex_data.insert(Axial::new(-3, 3), (hex::Resource::Sheep, 5));
-
-        hex_data.insert(Axial::new(-2, -2), (hex::Resource::Brick, 5));
-        hex_data.insert(Axial::new(-3, 0), (hex::Resource::Wheat, 6));
-        hex_data.insert(Axial::new(-4, 2), (hex::Resource::Sheep, 11));
-
+    pub fn from_hex_data(hex_data: HashMap<Axial, (hex::Resource, i32)>) -> Self {
         let mut hexes: HashMap<Axial, Hex> = HashMap::new();
         for (pos, ru) in hex_data.into_iter() {
             hexes.insert(
@@ -66,32 +47,34 @@ impl BoardData {
                 },
             );
         }
-        let mut vertices: HashMap<Axial, Vertex> = HashMap::new();
+        let mut vertices: HashMap<VertexCoord, Vertex> = HashMap::new();
+        let mut edges: HashMap<EdgeCoord, Edge> = HashMap::new();
         for hex in hexes.values() {
-            for &offset in OFFSETS.iter() {
-                let a: Axial = hex.pos + offset;
-                vertices.insert(a, Vertex::new(a, BuildType::None, -1));
+            for v in VertexCoord::corners_of(hex.pos) {
+                vertices.insert(v, Vertex::new(v, BuildType::None, None));
             }
-        }
-
-        let mut edges: HashMap<EdgeCoords, Edge> = HashMap::new();
-        for &v in vertices.keys() {
-            for &offset in OFFSETS.iter() {
-                let adjacent = v + offset;
-                if vertices.contains_key(&adjacent) {
-                    let coords = EdgeCoords::new(v, adjacent);
-                    let p = Edge::new(coords.clone(), super::edge::EdgeType::None);
-                    edges.insert(coords, p);
-                }
+            for coords in EdgeCoord::sides_of(hex.pos) {
+                edges.insert(coords, Edge::new(coords, EdgeType::None, None));
             }
         }
 
-        let board = BoardData {
+        //the robber starts on the desert
+        let robber = hexes
+            .values()
+            .find(|h| h.resource_type == hex::Resource::None)
+            .map(|h| h.pos)
+            .unwrap_or(Axial::new(0, 0));
+
+        let adjacency = Adjacency::new(&hexes, &vertices, &edges);
+        let mut board = BoardData {
             hexes,
             edges,
             vertices,
-            robber: Axial::new(0, 0),
+            harbors: Vec::new(),
+            robber,
+            adjacency,
         };
+        board.place_harbors(&HARBORS);
         board
     }
     pub fn get_valid_settlement_coords(&self) -> Vec<&Vertex> {
@@ -103,26 +86,208 @@ impl BoardData {
         }
         valid
     }
+    //outside of setup a new settlement also has to sit on one of the player's roads
+    pub fn get_connected_settlement_coords(&self, player: PlayerId) -> Vec<&Vertex> {
+        self.get_valid_settlement_coords()
+            .into_iter()
+            .filter(|v| {
+                self.get_incident_edges(v.pos)
+                    .iter()
+                    .any(|e| e.owner == Some(player))
+            })
+            .collect()
+    }
+    pub fn get_upgradable_settlements(&self, player: PlayerId) -> Vec<&Vertex> {
+        self.vertices
+            .values()
+            .filter(|v| v.owner == Some(player) && v.build_type == BuildType::Settlement)
+            .collect()
+    }
     pub fn get_adjacent_vertices(&self, v: &Vertex) -> Vec<&Vertex> {
+        v.pos
+            .adjacent_vertices()
+            .iter()
+            .filter_map(|a| self.vertices.get(a))
+            .collect()
+    }
+
+    pub fn get_valid_road_coords(&self, player: PlayerId) -> Vec<&Edge> {
         let mut valid = Vec::new();
-        for &offset in OFFSETS.iter() {
-            let adjacent = &(v.pos + offset);
-            if self.vertices.contains_key(adjacent) {
-                valid.push(&self.vertices[adjacent]);
+        for e in self.edges.values() {
+            if self.is_valid_road_coords(e, player) {
+                valid.push(e);
             }
         }
         valid
     }
+    pub fn get_incident_edges(&self, v: VertexCoord) -> Vec<&Edge> {
+        self.edges_of_vertex(v)
+            .iter()
+            .map(|e| &self.edges[e])
+            .collect()
+    }
+
+    pub fn get_adjacent_hexes(&self, v: VertexCoord) -> Vec<&Hex> {
+        self.hexes_of_vertex(v)
+            .iter()
+            .map(|h| &self.hexes[h])
+            .collect()
+    }
+
+    //what a click at a board position lands on, checked against the nearest lattice point and
+    //the ones around it since cells of the coarser hex grid span several lattice cells
+    pub fn hex_at(&self, pos: Vec2) -> Option<Axial> {
+        let p = LAYOUT.from_pixel(pos);
+        std::iter::once(p)
+            .chain(p.neighbors())
+            .filter(|a| a.is_hex_center())
+            .min_by(|a, b| {
+                let da = LAYOUT.to_pixel(*a).distance_squared(pos);
+                let db = LAYOUT.to_pixel(*b).distance_squared(pos);
+                da.total_cmp(&db)
+            })
+            .filter(|a| self.hexes.contains_key(a))
+    }
+    pub fn vertex_at(&self, pos: Vec2) -> Option<VertexCoord> {
+        VertexCoord::from_lattice(LAYOUT.from_pixel(pos)).filter(|v| self.vertices.contains_key(v))
+    }
+    //the closest road spot around the corner the click is nearest to
+    pub fn edge_at(&self, pos: Vec2) -> Option<EdgeCoord> {
+        let v = self.vertex_at(pos)?;
+        self.get_incident_edges(v)
+            .into_iter()
+            .map(|e| e.path_coords)
+            .min_by(|a, b| {
+                let da = a.get_cartesian().truncate().distance_squared(pos);
+                let db = b.get_cartesian().truncate().distance_squared(pos);
+                da.total_cmp(&db)
+            })
+    }
+
+    /// Resources paid out for a dice roll as (owner, resource, amount)
+    pub fn production(&self, roll: i32) -> Vec<(PlayerId, hex::Resource, u32)> {
+        let mut payouts = Vec::new();
+        for hex in self.hexes.values() {
+            if hex.number != roll || hex.pos == self.robber {
+                continue;
+            }
+            if let hex::Resource::None = hex.resource_type {
+                continue;
+            }
+            for v in self.buildings_on_hex(hex.pos) {
+                let amount = match v.build_type {
+                    BuildType::Settlement => 1,
+                    BuildType::City => 2,
+                    BuildType::None => continue,
+                };
+                if let Some(owner) = v.owner {
+                    payouts.push((owner, hex.resource_type, amount));
+                }
+            }
+        }
+        payouts
+    }
+
+    /// Owners of buildings on the corners of a hex, without duplicates
+    pub fn owners_on_hex(&self, pos: Axial) -> Vec<PlayerId> {
+        let mut owners = Vec::new();
+        for v in self.buildings_on_hex(pos) {
+            if let Some(owner) = v.owner {
+                if !owners.contains(&owner) {
+                    owners.push(owner);
+                }
+            }
+        }
+        owners
+    }
+
+    //a road has to touch the player's own building, or one of their roads through a corner
+    //that no opponent has built on
+    fn is_valid_road_coords(&self, e: &Edge, player: PlayerId) -> bool {
+        if e.owner.is_some() {
+            return false;
+        }
+        let (a, b) = e.path_coords.ends();
+        for end in [a, b] {
+            match self.vertices[&end].owner {
+                Some(owner) if owner == player => return true,
+                Some(_) => continue,
+                None => {},
+            }
+            if self
+                .get_incident_edges(end)
+                .iter()
+                .any(|other| other.owner == Some(player))
+            {
+                return true;
+            }
+        }
+        false
+    }
 
     fn is_valid_settlement_coords(&self, v: &Vertex) -> bool {
-        if v.owner != -1 {
+        if v.owner.is_some() {
             return false;
         }
         for a in self.get_adjacent_vertices(v) {
-            if a.owner != -1 {
+            if a.owner.is_some() {
                 return false;
             }
         }
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::generate::{GenerateOptions, BOARD_SEED};
+
+    //points around a spot out to radius, off the axes so none land exactly on a cell boundary
+    fn jitter(at: Vec2, radius: f32) -> Vec<Vec2> {
+        let mut points = vec![at];
+        for k in 0..12 {
+            let angle = (k as f32 * 30. + 7.).to_radians();
+            for scale in [0.3, 0.7, 1.] {
+                points.push(at + Vec2::from_angle(angle) * radius * scale);
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn picks_hex_under_point() {
+        let board = BoardData::generate(BOARD_SEED, GenerateOptions::default());
+        //a little under half way to the next hex center
+        let radius = LAYOUT.to_pixel(HEX_OFFSETS[0]).length() * 0.45;
+        for &pos in board.hexes.keys() {
+            for p in jitter(LAYOUT.to_pixel(pos), radius) {
+                assert_eq!(board.hex_at(p), Some(pos), "{:?} near {:?}", p, pos);
+            }
+        }
+    }
+
+    #[test]
+    fn picks_vertex_under_point() {
+        let board = BoardData::generate(BOARD_SEED, GenerateOptions::default());
+        //inside the lattice cell around the corner
+        let radius = LAYOUT.size.x * 0.8;
+        for &v in board.vertices.keys() {
+            for p in jitter(v.get_cartesian().truncate(), radius) {
+                assert_eq!(board.vertex_at(p), Some(v), "{:?} near {:?}", p, v);
+            }
+        }
+    }
+
+    #[test]
+    fn picks_edge_under_point() {
+        let board = BoardData::generate(BOARD_SEED, GenerateOptions::default());
+        //the midpoint sits on the border of the two corner cells, which is size long
+        let radius = LAYOUT.size.x * 0.4;
+        for &coords in board.edges.keys() {
+            for p in jitter(coords.get_cartesian().truncate(), radius) {
+                assert_eq!(board.edge_at(p), Some(coords), "{:?} near {:?}", p, coords);
+            }
+        }
+    }
+}
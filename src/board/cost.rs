@@ -0,0 +1,67 @@
+use std::fmt;
+
+use super::hex::Resource;
+
+//everything a player can spend resources on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Build {
+    Road,
+    Settlement,
+    City,
+    DevelopmentCard,
+}
+impl Build {
+    pub fn cost(&self) -> &'static [(Resource, u32)] {
+        match self {
+            Build::Road => &[(Resource::Brick, 1), (Resource::Wood, 1)],
+            Build::Settlement => &[
+                (Resource::Brick, 1),
+                (Resource::Wood, 1),
+                (Resource::Sheep, 1),
+                (Resource::Wheat, 1),
+            ],
+            Build::City => &[(Resource::Ore, 3), (Resource::Wheat, 2)],
+            Build::DevelopmentCard => &[
+                (Resource::Ore, 1),
+                (Resource::Wheat, 1),
+                (Resource::Sheep, 1),
+            ],
+        }
+    }
+}
+impl fmt::Display for Build {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Build::Road => "road",
+            Build::Settlement => "settlement",
+            Build::City => "city",
+            Build::DevelopmentCard => "development card",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    //what's still needed on top of what the player holds
+    CannotAfford {
+        build: Build,
+        missing: Vec<(Resource, u32)>,
+    },
+    OutOfPieces(Build),
+}
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::CannotAfford { build, missing } => {
+                write!(f, "can't afford a {}, missing", build)?;
+                for (resource, amount) in missing {
+                    write!(f, " {} {:?}", amount, resource)?;
+                }
+                Ok(())
+            },
+            BuildError::OutOfPieces(build) => write!(f, "no {} pieces left", build),
+        }
+    }
+}
+impl std::error::Error for BuildError {}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+
+use super::{
+    bank::Bank,
+    resource_cards::{spend_card, SpentCard},
+    GameState, RollStage,
+};
+use crate::{
+    card::{Ordinal, ResourceCard},
+    hand::{remove_from_hand, CardSelected, Hand},
+    player::{PlayerId, Players},
+};
+
+//players who still owe cards after a 7, the first one is discarding now
+#[derive(Resource, Default)]
+pub struct Discards {
+    pub owed: Vec<(PlayerId, u32)>,
+}
+
+pub fn owe_discards(players: Res<Players>, mut discards: ResMut<Discards>) {
+    discards.owed.clear();
+    for player in players.list.iter() {
+        let count = player.card_count();
+        if count > 7 {
+            discards.owed.push((player.id, count / 2));
+        }
+    }
+}
+
+//the hand shows the discarding player's cards, each one clicked goes back to the bank
+pub fn discard_cards(
+    mut cmd: Commands,
+    mut discards: ResMut<Discards>,
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
+    mut selected: EventReader<CardSelected>,
+    q_resource: Query<(&ResourceCard, &Transform), Without<SpentCard>>,
+    mut q_hand: Query<(&mut Hand, &Children)>,
+    mut q_ordinals: Query<&mut Ordinal>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_stage: ResMut<NextState<RollStage>>,
+) {
+    let (mut hand, children) = q_hand.single_mut();
+
+    for e in selected.iter() {
+        let Ok((resource, transform)) = q_resource.get(e.card) else {
+            continue;
+        };
+        let Some(owed) = discards.owed.first_mut() else {
+            break;
+        };
+        players.get_mut(owed.0).take_all(&[(resource.0, 1)]);
+        bank.put_all(&[(resource.0, 1)]);
+        owed.1 -= 1;
+        if owed.1 == 0 {
+            discards.owed.remove(0);
+        }
+        remove_from_hand(&mut hand, children, &[e.card], &mut q_ordinals);
+        spend_card(&mut cmd, e.card, transform.translation);
+    }
+
+    if discards.owed.is_empty() {
+        next_stage.set(RollStage::Dice);
+        next_state.set(GameState::Robber);
+    }
+}
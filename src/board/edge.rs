@@ -1,43 +1,201 @@
-use bevy::utils::HashSet;
+use bevy::prelude::Vec3;
 
-use super::axial::Axial;
+use super::{
+    axial::Axial,
+    vertex::{Corner, VertexCoord},
+};
+use crate::player::PlayerId;
 
 #[derive(Debug)]
 pub struct Edge {
-    pub path_coords: EdgeCoords,
+    pub path_coords: EdgeCoord,
     pub path_type: EdgeType,
+    pub owner: Option<PlayerId>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EdgeType {
     Road,
     None,
 }
 
 impl Edge {
-    pub fn new(path_coords: EdgeCoords, path_type: EdgeType) -> Self {
+    pub fn new(path_coords: EdgeCoord, path_type: EdgeType, owner: Option<PlayerId>) -> Self {
         Edge {
             path_coords,
             path_type,
+            owner,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
-pub struct EdgeCoords {
-    a: Axial,
-    b: Axial,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    NE,
+    NW,
+    W,
+}
+
+/// A side of a hex, every side is the NE, NW or W side of exactly one hex and the E, SE or SW
+/// side of the hex across from it, so only the first three are ever written down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EdgeCoord {
+    pub hex: Axial,
+    pub side: Side,
 }
-impl EdgeCoords {
-    pub fn new(a: Axial, b: Axial) -> EdgeCoords {
-        // Ensure that UnorderedPairCoords is always ordered lexicographically
-        if a < b {
-            EdgeCoords { a, b }
-        } else {
-            EdgeCoords { b, a }
+impl EdgeCoord {
+    pub fn new(hex: Axial, side: Side) -> Self {
+        EdgeCoord { hex, side }
+    }
+
+    /// The edge running between two corners, None unless they're next to each other
+    pub fn between(a: VertexCoord, b: VertexCoord) -> Option<Self> {
+        a.hexes()
+            .into_iter()
+            .flat_map(|h| [Side::NE, Side::NW, Side::W].map(|side| EdgeCoord::new(h, side)))
+            .find(|e| {
+                let (x, y) = e.ends();
+                (x, y) == (a, b) || (y, x) == (a, b)
+            })
+    }
+
+    /// The six sides of a hex, clockwise from the top right
+    pub fn sides_of(hex: Axial) -> [EdgeCoord; 6] {
+        let corners = VertexCoord::corners_of(hex);
+        let mut i = 0;
+        [(); 6].map(|_| {
+            let (a, b) = (corners[i], corners[(i + 1) % 6]);
+            i += 1;
+            EdgeCoord::between(a, b).expect("neighbouring corners of a hex share an edge")
+        })
+    }
+
+    pub fn ends(&self) -> (VertexCoord, VertexCoord) {
+        let h = self.hex;
+        match self.side {
+            Side::NE => (
+                VertexCoord::new(h, Corner::N),
+                VertexCoord::new(h + Axial::new(1, 1), Corner::S),
+            ),
+            Side::NW => (
+                VertexCoord::new(h + Axial::new(2, -1), Corner::S),
+                VertexCoord::new(h, Corner::N),
+            ),
+            Side::W => (
+                VertexCoord::new(h + Axial::new(2, -1), Corner::S),
+                VertexCoord::new(h + Axial::new(-1, -1), Corner::N),
+            ),
         }
     }
-    pub fn contains(&self, c: Axial) -> bool {
-        self.a == c || self.b == c
+    pub fn contains(&self, v: VertexCoord) -> bool {
+        let (a, b) = self.ends();
+        a == v || b == v
+    }
+    //the middle of the edge
+    pub fn get_cartesian(&self) -> Vec3 {
+        let (a, b) = self.ends();
+        (a.get_cartesian() + b.get_cartesian()) / 2.
+    }
+
+    /// The two hexes on either side, whether they're on the board or not
+    pub fn hexes(&self) -> [Axial; 2] {
+        let across = match self.side {
+            Side::NE => Axial::new(1, 1),
+            Side::NW => Axial::new(2, -1),
+            Side::W => Axial::new(1, -2),
+        };
+        [self.hex, self.hex + across]
+    }
+
+    /// The four edges sharing a corner with this one
+    pub fn adjacent_edges(&self) -> Vec<EdgeCoord> {
+        let (a, b) = self.ends();
+        a.edges()
+            .into_iter()
+            .chain(b.edges())
+            .filter(|e| e != self)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Vec2;
+
+    use super::*;
+    use crate::board::{
+        board_data::BoardData,
+        generate::{GenerateOptions, BOARD_SEED},
+        map::Facing,
+    };
+
+    fn board() -> BoardData {
+        BoardData::generate(BOARD_SEED, GenerateOptions::default())
+    }
+
+    #[test]
+    fn interior_vertices_have_three_of_everything() {
+        let board = board();
+        let mut interior = 0;
+        for &v in board.vertices.keys() {
+            if !v.hexes().iter().all(|h| board.hexes.contains_key(h)) {
+                continue;
+            }
+            interior += 1;
+            assert_eq!(board.hexes_of_vertex(v).len(), 3, "{:?}", v);
+            assert_eq!(board.edges_of_vertex(v).len(), 3, "{:?}", v);
+            for h in v.hexes() {
+                assert!(VertexCoord::corners_of(h).contains(&v));
+            }
+        }
+        //the standard board has 24 corners inside the coast
+        assert_eq!(interior, 24);
+    }
+
+    #[test]
+    fn between_is_symmetric() {
+        let board = board();
+        for &a in board.vertices.keys() {
+            for &b in board.vertices.keys() {
+                let e = EdgeCoord::between(a, b);
+                assert_eq!(e, EdgeCoord::between(b, a));
+                assert_eq!(e.is_some(), a.adjacent_vertices().contains(&b));
+                if let Some(e) = e {
+                    assert!(e.contains(a) && e.contains(b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sides_follow_facing() {
+        let compass = [
+            (Facing::NE, 60.),
+            (Facing::E, 0.),
+            (Facing::SE, -60.),
+            (Facing::SW, -120.),
+            (Facing::W, 180.),
+            (Facing::NW, 120.),
+        ];
+        for h in [Axial::new(0, 0), Axial::new(1, 1), Axial::new(-2, 1)] {
+            let sides = EdgeCoord::sides_of(h);
+            for (facing, degrees) in compass {
+                let d = sides[facing as usize].get_cartesian() - h.get_cartesian();
+                let angle =
+                    Vec2::new(d.x, d.y).angle_between(Vec2::from_angle(f32::to_radians(degrees)));
+                assert!(angle.abs() < 1e-3, "{:?} of {:?}", facing, h);
+                assert!(sides[facing as usize].hexes().contains(&h));
+            }
+        }
+    }
+
+    #[test]
+    fn adjacent_edges_share_a_corner() {
+        for coords in EdgeCoord::sides_of(Axial::new(0, 0)) {
+            let (a, b) = coords.ends();
+            let adjacent = coords.adjacent_edges();
+            assert_eq!(adjacent.len(), 4);
+            assert!(adjacent.iter().all(|e| e.contains(a) != e.contains(b)));
+        }
     }
 }
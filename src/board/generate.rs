@@ -0,0 +1,374 @@
+use bevy::utils::HashMap;
+use random::Source;
+
+use super::{
+    axial::Axial,
+    board_data::BoardData,
+    harbor::{HarborKind, HARBORS},
+    hex,
+};
+
+pub const BOARD_SEED: u64 = 0xb0a2d;
+const MAX_ATTEMPTS: usize = 5000;
+
+const RESOURCES: [(hex::Resource, usize); 6] = [
+    (hex::Resource::Wood, 4),
+    (hex::Resource::Sheep, 4),
+    (hex::Resource::Wheat, 4),
+    (hex::Resource::Brick, 3),
+    (hex::Resource::Ore, 3),
+    (hex::Resource::None, 1),
+];
+const NUMBERS: [i32; 18] = [2, 3, 3, 4, 4, 5, 5, 6, 6, 8, 8, 9, 9, 10, 10, 11, 11, 12];
+//where the 19 hexes of the standard board go
+const STANDARD_POSITIONS: [(i32, i32); 19] = [
+    (4, -2),
+    (3, 0),
+    (2, 2),
+    (3, -3),
+    (2, -1),
+    (1, 1),
+    (0, 3),
+    (2, -4),
+    (1, -2),
+    (0, 0),
+    (-1, 2),
+    (-2, 4),
+    (0, -3),
+    (-1, -1),
+    (-2, 1),
+    (-3, 3),
+    (-2, -2),
+    (-3, 0),
+    (-4, 2),
+];
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GenerateOptions {
+    pub no_adjacent_six_eight: bool,
+    pub no_adjacent_same_number: bool,
+    pub no_resource_clusters: bool,
+}
+impl GenerateOptions {
+    pub fn balanced() -> Self {
+        GenerateOptions {
+            no_adjacent_six_eight: true,
+            no_adjacent_same_number: true,
+            no_resource_clusters: true,
+        }
+    }
+}
+
+//number of dots on a token, out of the 36 possible rolls
+pub fn pips(number: i32) -> i32 {
+    if (2..=12).contains(&number) && number != 7 {
+        6 - (7 - number).abs()
+    } else {
+        0
+    }
+}
+
+impl BoardData {
+    /// Shuffle the standard tiles, number tokens and harbors onto the board. The same seed always
+    /// gives the same board, if a constraint can't be met the board breaking the fewest rules is
+    /// kept
+    pub fn generate(seed: u64, options: GenerateOptions) -> Self {
+        let positions: Vec<Axial> = STANDARD_POSITIONS
+            .iter()
+            .map(|&(q, r)| Axial::new(q, r))
+            .collect();
+        Self::deal(
+            seed,
+            options,
+            &positions,
+            &resource_bag(),
+            &NUMBERS,
+            &HARBORS,
+        )
+    }
+
+    /// Shuffle any set of tiles, number tokens and harbors onto the given positions, there has to
+    /// be a tile for every position and a token for every tile that isn't desert
+    pub fn deal(
+        seed: u64,
+        options: GenerateOptions,
+        positions: &[Axial],
+        tiles: &[hex::Resource],
+        numbers: &[i32],
+        harbors: &[HarborKind],
+    ) -> Self {
+        let mut source = random::default(seed);
+
+        let resources = best_shuffle(&mut source, tiles, |resources| {
+            let tiles: HashMap<Axial, hex::Resource> = positions
+                .iter()
+                .copied()
+                .zip(resources.iter().copied())
+                .collect();
+            if options.no_resource_clusters {
+                count_adjacent(&tiles, |a, b| a != hex::Resource::None && a == b)
+            } else {
+                0
+            }
+        });
+
+        let numbers = best_shuffle(&mut source, numbers, |numbers| {
+            let tokens = place_numbers(positions, &resources, numbers);
+            let mut violations = 0;
+            if options.no_adjacent_six_eight {
+                violations += count_adjacent(&tokens, |a, b| is_red(a) && is_red(b));
+            }
+            if options.no_adjacent_same_number {
+                violations += count_adjacent(&tokens, |a, b| a != 0 && a == b);
+            }
+            violations
+        });
+
+        let tokens = place_numbers(positions, &resources, &numbers);
+        let hex_data = positions
+            .iter()
+            .zip(resources.iter())
+            .map(|(pos, &resource)| (*pos, (resource, tokens[pos])))
+            .collect();
+        let mut board = Self::from_hex_data(hex_data);
+        let mut harbors = harbors.to_vec();
+        shuffle(&mut source, &mut harbors);
+        board.place_harbors(&harbors);
+        board
+    }
+
+    /// How evenly production is spread, 1.0 is perfectly even. Combines how close each resource
+    /// is to the same average pips per tile with a penalty for corners touching too many pips
+    pub fn fairness(&self) -> f32 {
+        let mut per_resource: HashMap<hex::Resource, (i32, i32)> = HashMap::new();
+        for h in self.hexes.values() {
+            if h.resource_type == hex::Resource::None {
+                continue;
+            }
+            let entry = per_resource.entry(h.resource_type).or_default();
+            entry.0 += pips(h.number);
+            entry.1 += 1;
+        }
+        let averages: Vec<f32> = per_resource
+            .values()
+            .map(|&(p, n)| p as f32 / n as f32)
+            .collect();
+        if averages.is_empty() {
+            return 0.;
+        }
+        let mean = averages.iter().sum::<f32>() / averages.len() as f32;
+        let variance =
+            averages.iter().map(|a| (a - mean).powi(2)).sum::<f32>() / averages.len() as f32;
+        let spread = if mean > 0. {
+            variance.sqrt() / mean
+        } else {
+            1.
+        };
+
+        let hottest = self
+            .vertices
+            .keys()
+            .map(|&v| {
+                self.get_adjacent_hexes(v)
+                    .iter()
+                    .map(|h| pips(h.number))
+                    .sum::<i32>()
+            })
+            .max()
+            .unwrap_or(0);
+        //three 5 pip tokens on one corner is the worst case
+        let hotspot = (hottest - 10).max(0) as f32 / 5.;
+
+        (1. - spread).clamp(0., 1.) * (1. - 0.5 * hotspot.min(1.))
+    }
+}
+
+fn resource_bag() -> Vec<hex::Resource> {
+    let mut bag = Vec::new();
+    for &(resource, count) in RESOURCES.iter() {
+        for _ in 0..count {
+            bag.push(resource);
+        }
+    }
+    bag
+}
+
+fn is_red(number: i32) -> bool {
+    number == 6 || number == 8
+}
+
+fn place_numbers(
+    positions: &[Axial],
+    resources: &[hex::Resource],
+    numbers: &[i32],
+) -> HashMap<Axial, i32> {
+    let mut numbers = numbers.iter();
+    positions
+        .iter()
+        .zip(resources.iter())
+        .map(|(&pos, &resource)| {
+            let number = match resource {
+                hex::Resource::None => 0,
+                _ => numbers.next().copied().unwrap_or(0),
+            };
+            (pos, number)
+        })
+        .collect()
+}
+
+//pairs of neighbouring hexes matching a rule, each pair counted once
+fn count_adjacent<T: Copy>(tiles: &HashMap<Axial, T>, rule: impl Fn(T, T) -> bool) -> usize {
+    let mut count = 0;
+    for (&pos, &a) in tiles.iter() {
+        let Some(steps) = pos.to_hex_steps() else {
+            continue;
+        };
+        for other in steps.neighbors().map(Axial::from_hex_steps) {
+            if other < pos {
+                continue;
+            }
+            if let Some(&b) = tiles.get(&other) {
+                if rule(a, b) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    count
+}
+
+//keep shuffling until nothing is violated, falling back to the least bad order
+fn best_shuffle<T: Copy>(
+    source: &mut random::Default,
+    items: &[T],
+    violations: impl Fn(&[T]) -> usize,
+) -> Vec<T> {
+    let mut best = items.to_vec();
+    let mut best_violations = usize::MAX;
+    for _ in 0..MAX_ATTEMPTS {
+        let mut candidate = items.to_vec();
+        shuffle(source, &mut candidate);
+        let v = violations(&candidate);
+        if v < best_violations {
+            best = candidate;
+            best_violations = v;
+        }
+        if best_violations == 0 {
+            break;
+        }
+    }
+    best
+}
+
+pub fn shuffle<T>(source: &mut random::Default, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+        let j = (source.read_u64() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(board: &BoardData) -> Vec<(Axial, hex::Resource, i32)> {
+        let mut hexes: Vec<_> = board
+            .hexes
+            .iter()
+            .map(|(&pos, h)| (pos, h.resource_type, h.number))
+            .collect();
+        hexes.sort_by_key(|h| h.0);
+        hexes
+    }
+
+    fn violations(board: &BoardData, options: GenerateOptions) -> usize {
+        let resources: HashMap<Axial, hex::Resource> = board
+            .hexes
+            .iter()
+            .map(|(&pos, h)| (pos, h.resource_type))
+            .collect();
+        let numbers: HashMap<Axial, i32> = board
+            .hexes
+            .iter()
+            .map(|(&pos, h)| (pos, h.number))
+            .collect();
+        let mut count = 0;
+        if options.no_resource_clusters {
+            count += count_adjacent(&resources, |a, b| a != hex::Resource::None && a == b);
+        }
+        if options.no_adjacent_six_eight {
+            count += count_adjacent(&numbers, |a, b| is_red(a) && is_red(b));
+        }
+        if options.no_adjacent_same_number {
+            count += count_adjacent(&numbers, |a, b| a != 0 && a == b);
+        }
+        count
+    }
+
+    #[test]
+    fn same_seed_same_board() {
+        for seed in [0, 1, BOARD_SEED, u64::MAX] {
+            let a = BoardData::generate(seed, GenerateOptions::balanced());
+            let b = BoardData::generate(seed, GenerateOptions::balanced());
+            assert_eq!(layout(&a), layout(&b));
+            let harbors = |board: &BoardData| {
+                board
+                    .harbors
+                    .iter()
+                    .map(|h| (h.coords, h.kind))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(harbors(&a), harbors(&b));
+        }
+    }
+
+    #[test]
+    fn deals_the_standard_set() {
+        let board = BoardData::generate(7, GenerateOptions::default());
+        assert_eq!(board.hexes.len(), 19);
+        let mut tiles: Vec<_> = board.hexes.values().map(|h| h.resource_type).collect();
+        let mut bag = resource_bag();
+        tiles.sort_by_key(|r| *r as usize);
+        bag.sort_by_key(|r| *r as usize);
+        assert_eq!(tiles, bag);
+        let mut numbers: Vec<_> = board
+            .hexes
+            .values()
+            .map(|h| h.number)
+            .filter(|&n| n != 0)
+            .collect();
+        numbers.sort();
+        assert_eq!(numbers, NUMBERS);
+    }
+
+    #[test]
+    fn constraints_are_met() {
+        let each = [
+            GenerateOptions {
+                no_adjacent_six_eight: true,
+                ..Default::default()
+            },
+            GenerateOptions {
+                no_adjacent_same_number: true,
+                ..Default::default()
+            },
+            GenerateOptions {
+                no_resource_clusters: true,
+                ..Default::default()
+            },
+            GenerateOptions::balanced(),
+        ];
+        for options in each {
+            for seed in 0..20 {
+                let board = BoardData::generate(seed, options);
+                assert_eq!(
+                    violations(&board, options),
+                    0,
+                    "{:?} seed {}",
+                    options,
+                    seed
+                );
+            }
+        }
+    }
+}
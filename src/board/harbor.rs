@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{axial::Axial, board_data::BoardData, edge::EdgeCoord, hex, vertex::VertexCoord};
+use crate::player::PlayerId;
+
+//bank trades without a harbor
+pub const DEFAULT_RATIO: u32 = 4;
+
+//the standard nine harbors in the order they are laid around the coast
+pub const HARBORS: [HarborKind; 9] = [
+    HarborKind::Generic,
+    HarborKind::Special(hex::Resource::Sheep),
+    HarborKind::Generic,
+    HarborKind::Special(hex::Resource::Ore),
+    HarborKind::Generic,
+    HarborKind::Special(hex::Resource::Wheat),
+    HarborKind::Special(hex::Resource::Brick),
+    HarborKind::Generic,
+    HarborKind::Special(hex::Resource::Wood),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum HarborKind {
+    //3:1 for any resource
+    Generic,
+    //2:1 for one resource
+    Special(hex::Resource),
+}
+impl HarborKind {
+    pub fn ratio(&self) -> u32 {
+        match self {
+            HarborKind::Generic => 3,
+            HarborKind::Special(_) => 2,
+        }
+    }
+    pub fn applies_to(&self, resource: hex::Resource) -> bool {
+        match self {
+            HarborKind::Generic => true,
+            HarborKind::Special(r) => *r == resource,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Harbor {
+    pub coords: EdgeCoord,
+    pub kind: HarborKind,
+    //the land hex the harbor is on, it faces away from it
+    pub hex: Axial,
+}
+
+impl BoardData {
+    /// Edges on the edge of the island as (edge, the only hex touching it), in order going
+    /// around the board
+    pub fn coastal_edges(&self) -> Vec<(EdgeCoord, Axial)> {
+        let mut coastal: Vec<(EdgeCoord, Axial)> = self
+            .edges
+            .keys()
+            .filter_map(|&coords| {
+                let land: Vec<Axial> = coords
+                    .hexes()
+                    .into_iter()
+                    .filter(|h| self.hexes.contains_key(h))
+                    .collect();
+                match land[..] {
+                    [only] => Some((coords, only)),
+                    _ => None,
+                }
+            })
+            .collect();
+        let center = self.center();
+        let angle = |coords: &EdgeCoord| {
+            let mid = coords.get_cartesian() - center;
+            mid.y.atan2(mid.x)
+        };
+        coastal.sort_by(|(a, _), (b, _)| angle(a).total_cmp(&angle(b)));
+        coastal
+    }
+
+    /// Spread harbors evenly along the coast, one for each kind given while there is room.
+    /// Two harbors never share a corner
+    pub fn place_harbors(&mut self, kinds: &[HarborKind]) {
+        let coastal = self.coastal_edges();
+        self.harbors.clear();
+        let kinds = &kinds[..kinds.len().min(coastal.len())];
+        let mut taken: Vec<VertexCoord> = Vec::new();
+        for (i, &kind) in kinds.iter().enumerate() {
+            //from the evenly spaced spot, go on to the first edge clear of the other harbors
+            let start = i * coastal.len() / kinds.len();
+            let free = (0..coastal.len())
+                .map(|step| coastal[(start + step) % coastal.len()])
+                .find(|(coords, _)| {
+                    let (a, b) = coords.ends();
+                    !taken.contains(&a) && !taken.contains(&b)
+                });
+            let Some((coords, hex)) = free else {
+                break;
+            };
+            let (a, b) = coords.ends();
+            taken.extend([a, b]);
+            self.harbors.push(Harbor { coords, kind, hex });
+        }
+    }
+
+    /// How many of a resource the player has to give the bank for one card
+    pub fn trade_ratio(&self, player: PlayerId, resource: hex::Resource) -> u32 {
+        self.harbors
+            .iter()
+            .filter(|h| h.kind.applies_to(resource))
+            .filter(|h| {
+                self.vertices_of_edge(h.coords)
+                    .iter()
+                    .any(|v| self.vertices[v].owner == Some(player))
+            })
+            .map(|h| h.kind.ratio())
+            .min()
+            .unwrap_or(DEFAULT_RATIO)
+    }
+
+    pub fn center(&self) -> Vec3 {
+        if self.hexes.is_empty() {
+            return Vec3::ZERO;
+        }
+        self.hexes.keys().map(|h| h.get_cartesian()).sum::<Vec3>() / self.hexes.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::{HashMap, HashSet};
+
+    use super::*;
+    use crate::board::generate::{GenerateOptions, BOARD_SEED};
+
+    fn shared_corners(harbors: &[Harbor]) -> bool {
+        let corners: HashSet<VertexCoord> = harbors
+            .iter()
+            .flat_map(|h| {
+                let (a, b) = h.coords.ends();
+                [a, b]
+            })
+            .collect();
+        corners.len() != harbors.len() * 2
+    }
+
+    #[test]
+    fn standard_board_gets_every_harbor() {
+        let board = BoardData::generate(BOARD_SEED, GenerateOptions::default());
+        assert_eq!(board.harbors.len(), HARBORS.len());
+        assert!(!shared_corners(&board.harbors));
+    }
+
+    #[test]
+    fn small_coast_gets_fewer_harbors() {
+        let mut board = BoardData::from_hex_data(HashMap::from_iter([(
+            Axial::new(0, 0),
+            (hex::Resource::Wood, 6),
+        )]));
+        board.place_harbors(&HARBORS);
+        //six edges around a lone hex only fit three harbors without touching
+        assert_eq!(board.harbors.len(), 3);
+        assert!(!shared_corners(&board.harbors));
+    }
+}
@@ -1,3 +1,6 @@
+use bevy::prelude::Color;
+use serde::Deserialize;
+
 use super::axial::Axial;
 
 #[derive(Clone, Copy, Debug)]
@@ -7,14 +10,23 @@ pub struct Hex {
     pub pos: Axial,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BuildType {
     City,
     Settlement,
     None,
 }
-#[derive(Clone, Copy, Debug)]
+//every resource a card can be, the desert gives nothing
+pub const RESOURCES: [Resource; 5] = [
+    Resource::Wood,
+    Resource::Brick,
+    Resource::Sheep,
+    Resource::Wheat,
+    Resource::Ore,
+];
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
 pub enum Resource {
+    #[serde(rename = "Desert")]
     None,
     Ore,
     Wheat,
@@ -22,3 +34,14 @@ pub enum Resource {
     Brick,
     Wood,
 }
+
+pub fn resource_tint(resource: Resource) -> Color {
+    match resource {
+        Resource::None => Color::WHITE,
+        Resource::Ore => Color::rgb(0.6, 0.6, 0.7),
+        Resource::Wheat => Color::rgb(1., 0.85, 0.3),
+        Resource::Sheep => Color::rgb(0.6, 0.9, 0.4),
+        Resource::Brick => Color::rgb(0.85, 0.4, 0.25),
+        Resource::Wood => Color::rgb(0.3, 0.6, 0.3),
+    }
+}
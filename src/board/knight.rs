@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+
+use super::{despawn_pickers, GameState, PickablePos};
+use crate::{
+    camera::lerp,
+    card::{FlipCard, Ordinal},
+    deck::{DevCard, DevelopmentCard},
+    hand::{remove_from_hand, CardPlayed, Hand},
+    player::{BonusCard, PlayerId, Players},
+};
+
+//fewest knights that can hold the bonus
+pub const LARGEST_ARMY_MIN: u32 = 3;
+
+//where a player's played cards are laid out face up, one row per player along the top
+#[derive(Component)]
+pub struct PlayedCards(pub PlayerId);
+
+pub fn spawn_played_areas(mut cmd: Commands, players: Res<Players>) {
+    for id in players.ids() {
+        cmd.spawn((
+            SpatialBundle {
+                transform: Transform {
+                    translation: Vec3::new(-450. + id.0 as f32 * 300., 300., 0.),
+                    scale: Vec3::splat(0.5),
+                    ..default()
+                },
+                ..default()
+            },
+            PlayedCards(id),
+        ));
+    }
+}
+
+pub fn position_played_cards(
+    q_areas: Query<&Children, With<PlayedCards>>,
+    mut q_cards: Query<(&Ordinal, &mut Transform)>,
+) {
+    for children in q_areas.iter() {
+        for &child in children.iter() {
+            if let Ok((ord, mut transform)) = q_cards.get_mut(child) {
+                let x = ord.0 as f32 * 40.;
+                transform.translation.x = lerp(transform.translation.x, x, 0.2);
+                transform.translation.y = lerp(transform.translation.y, 0., 0.2);
+                transform.translation.z = ord.0 as f32;
+            }
+        }
+    }
+}
+
+//dragging a knight out of the hand sends the robber off and grows the player's army
+pub fn play_knight(
+    mut cmd: Commands,
+    mut players: ResMut<Players>,
+    mut played: EventReader<CardPlayed>,
+    q_dev: Query<&DevelopmentCard>,
+    mut q_hand: Query<(&mut Hand, &Children)>,
+    mut q_ordinals: Query<&mut Ordinal>,
+    mut q_transforms: Query<&mut Transform, With<Ordinal>>,
+    q_areas: Query<(Entity, &PlayedCards, &Transform, Option<&Children>), Without<Ordinal>>,
+    pickers: Query<Entity, With<PickablePos>>,
+    mut flip_writer: EventWriter<FlipCard>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for e in played.iter() {
+        let Ok(dev_card) = q_dev.get(e.card) else {
+            continue;
+        };
+        if dev_card.kind != DevCard::Knight {
+            continue;
+        }
+        if !dev_card.playable(&players) {
+            info!("that knight can't be played yet");
+            continue;
+        }
+
+        let (mut hand, children) = q_hand.single_mut();
+        remove_from_hand(&mut hand, children, &[e.card], &mut q_ordinals);
+        if let Some((area, _, area_transform, played_cards)) =
+            q_areas.iter().find(|(_, p, _, _)| p.0 == players.active)
+        {
+            if let Ok(mut ord) = q_ordinals.get_mut(e.card) {
+                ord.0 = played_cards.map_or(0, |c| c.len());
+            }
+            //keep the card where it was dropped, it slides into the row from there
+            if let Ok(mut transform) = q_transforms.get_mut(e.card) {
+                transform.translation =
+                    (transform.translation - area_transform.translation) / area_transform.scale;
+            }
+            cmd.entity(e.card).remove_parent();
+            cmd.entity(area).push_children(&[e.card]);
+        }
+        cmd.entity(e.card).remove::<DevelopmentCard>();
+        flip_writer.send(FlipCard { card: e.card });
+
+        let player = players.active_mut();
+        player.army += 1;
+        info!("{} plays a knight, army of {}", player.name, player.army);
+        award_largest_army(&mut players);
+        //open build pickers would still buy a piece while the robber moves
+        despawn_pickers(&mut cmd, &pickers);
+        next_state.set(GameState::Robber);
+        return;
+    }
+}
+
+//the bonus only moves to a strictly bigger army
+fn award_largest_army(players: &mut Players) {
+    let holder = players
+        .list
+        .iter()
+        .find(|p| p.bonus_cards.contains(&BonusCard::LargestArmy))
+        .map(|p| p.id);
+    let active = players.active();
+    if active.army < LARGEST_ARMY_MIN || Some(active.id) == holder {
+        return;
+    }
+    if holder.is_some_and(|h| players.get(h).army >= active.army) {
+        return;
+    }
+
+    if let Some(h) = holder {
+        players
+            .get_mut(h)
+            .bonus_cards
+            .retain(|&c| c != BonusCard::LargestArmy);
+    }
+    let player = players.active_mut();
+    info!("{} takes the largest army", player.name);
+    player.bonus_cards.push(BonusCard::LargestArmy);
+}
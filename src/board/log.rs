@@ -0,0 +1,87 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+use super::hex;
+use crate::player::PlayerId;
+
+//things players did that other players should be able to look back on
+#[derive(Debug, Clone)]
+pub enum GameAction {
+    RoadBuilding {
+        player: PlayerId,
+    },
+    YearOfPlenty {
+        player: PlayerId,
+        resources: Vec<hex::Resource>,
+    },
+    Monopoly {
+        player: PlayerId,
+        resource: hex::Resource,
+        taken: u32,
+    },
+    Trade {
+        from: PlayerId,
+        to: PlayerId,
+        give: Vec<(hex::Resource, u32)>,
+        get: Vec<(hex::Resource, u32)>,
+    },
+}
+
+impl fmt::Display for GameAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        //players are numbered from 1 on screen
+        let name = |p: &PlayerId| p.0 + 1;
+        match self {
+            GameAction::RoadBuilding { player } => {
+                write!(f, "player {} plays road building", name(player))
+            },
+            GameAction::YearOfPlenty { player, resources } => {
+                write!(
+                    f,
+                    "player {} takes {:?} for year of plenty",
+                    name(player),
+                    resources
+                )
+            },
+            GameAction::Monopoly {
+                player,
+                resource,
+                taken,
+            } => write!(
+                f,
+                "player {} takes every {:?}, {} in all",
+                name(player),
+                resource,
+                taken
+            ),
+            GameAction::Trade {
+                from,
+                to,
+                give,
+                get,
+            } => {
+                write!(f, "player {} gives player {}", name(from), name(to))?;
+                for (resource, amount) in give {
+                    write!(f, " {} {:?}", amount, resource)?;
+                }
+                write!(f, " for")?;
+                for (resource, amount) in get {
+                    write!(f, " {} {:?}", amount, resource)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct GameLog {
+    pub actions: Vec<GameAction>,
+}
+impl GameLog {
+    pub fn record(&mut self, action: GameAction) {
+        info!("{}", action);
+        self.actions.push(action);
+    }
+}
@@ -0,0 +1,322 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use super::{board_data::BoardData, edge::EdgeCoord, vertex::VertexCoord};
+use crate::player::{BonusCard, PlayerId, Players};
+
+//fewest road segments that can hold the bonus
+pub const LONGEST_ROAD_MIN: u32 = 5;
+
+impl BoardData {
+    /// Length of the longest trail over a player's roads, every road is used at most once and an
+    /// opponent's building on a corner cuts the trail there
+    pub fn longest_road(&self, player: PlayerId) -> u32 {
+        let mut starts: Vec<VertexCoord> = Vec::new();
+        for e in self.edges.values() {
+            if e.owner != Some(player) {
+                continue;
+            }
+            let (a, b) = e.path_coords.ends();
+            for end in [a, b] {
+                if !starts.contains(&end) {
+                    starts.push(end);
+                }
+            }
+        }
+        let mut used = HashSet::new();
+        starts
+            .into_iter()
+            .map(|start| self.trail(player, start, &mut used))
+            .max()
+            .unwrap_or(0)
+    }
+
+    //longest way on from a corner without reusing a road, loops are fine since only roads are
+    //remembered and not the corners
+    fn trail(&self, player: PlayerId, from: VertexCoord, used: &mut HashSet<EdgeCoord>) -> u32 {
+        let mut best = 0;
+        for e in self.get_incident_edges(from) {
+            if e.owner != Some(player) || used.contains(&e.path_coords) {
+                continue;
+            }
+            let (a, b) = e.path_coords.ends();
+            let next = if a == from { b } else { a };
+            let blocked = matches!(self.vertices[&next].owner, Some(owner) if owner != player);
+
+            used.insert(e.path_coords);
+            let length = if blocked {
+                1
+            } else {
+                1 + self.trail(player, next, used)
+            };
+            used.remove(&e.path_coords);
+            best = best.max(length);
+        }
+        best
+    }
+}
+
+//hand the bonus to a strictly longer road, a tie never takes it away from whoever has it.
+//if the holder gets cut below someone else and that spot is shared the bonus is set aside
+pub fn award_longest_road(board: Res<BoardData>, mut players: ResMut<Players>) {
+    if !board.is_changed() {
+        return;
+    }
+    let lengths: Vec<(PlayerId, u32)> = players
+        .ids()
+        .map(|id| (id, board.longest_road(id)))
+        .collect();
+    let holder = players
+        .list
+        .iter()
+        .find(|p| p.bonus_cards.contains(&BonusCard::LongestRoad))
+        .map(|p| p.id);
+    let best = lengths.iter().map(|&(_, l)| l).max().unwrap_or(0);
+
+    let new_holder = if best < LONGEST_ROAD_MIN {
+        None
+    } else if holder.is_some_and(|h| lengths[h.0].1 == best) {
+        holder
+    } else {
+        let leaders: Vec<PlayerId> = lengths
+            .iter()
+            .filter(|&&(_, l)| l == best)
+            .map(|&(id, _)| id)
+            .collect();
+        match leaders[..] {
+            [leader] => Some(leader),
+            _ => None,
+        }
+    };
+    if new_holder == holder {
+        return;
+    }
+
+    if let Some(h) = holder {
+        players
+            .get_mut(h)
+            .bonus_cards
+            .retain(|&c| c != BonusCard::LongestRoad);
+    }
+    if let Some(h) = new_holder {
+        info!(
+            "{} takes the longest road with {}",
+            players.get(h).name,
+            best
+        );
+        players.get_mut(h).bonus_cards.push(BonusCard::LongestRoad);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::utils::HashMap;
+
+    use super::*;
+    use crate::board::{
+        axial::Axial,
+        board_data::HEX_OFFSETS,
+        hex::{self, BuildType},
+    };
+
+    const P0: PlayerId = PlayerId(0);
+    const P1: PlayerId = PlayerId(1);
+    const P2: PlayerId = PlayerId(2);
+
+    //the center hex and two rings around it
+    fn board() -> BoardData {
+        let mut hex_data = HashMap::new();
+        hex_data.insert(Axial::new(0, 0), (hex::Resource::Wood, 5));
+        for &a in HEX_OFFSETS.iter() {
+            for &b in HEX_OFFSETS.iter() {
+                hex_data.insert(a, (hex::Resource::Wood, 5));
+                hex_data.insert(a + b, (hex::Resource::Wood, 5));
+            }
+        }
+        BoardData::from_hex_data(hex_data)
+    }
+
+    fn roads(board: &mut BoardData, player: PlayerId, path: &[VertexCoord]) {
+        for pair in path.windows(2) {
+            let coords = EdgeCoord::between(pair[0], pair[1]).expect("corners are adjacent");
+            board
+                .edges
+                .get_mut(&coords)
+                .expect("road on the board")
+                .owner = Some(player);
+        }
+    }
+
+    fn settle(board: &mut BoardData, player: PlayerId, pos: VertexCoord) {
+        let v = board.vertices.get_mut(&pos).expect("corner on the board");
+        v.build_type = BuildType::Settlement;
+        v.owner = Some(player);
+    }
+
+    //keeps going from the end of a path to a corner it hasn't visited yet
+    fn extend(path: &mut Vec<VertexCoord>, steps: usize, avoid: &[VertexCoord]) {
+        for _ in 0..steps {
+            let last = *path.last().unwrap();
+            let next = last
+                .adjacent_vertices()
+                .into_iter()
+                .find(|v| !path.contains(v) && !avoid.contains(v))
+                .unwrap();
+            path.push(next);
+        }
+    }
+
+    //the first n+1 corners of a hex, n roads around its edge
+    fn around(hex: Axial, n: usize) -> Vec<VertexCoord> {
+        VertexCoord::corners_of(hex)[..=n].to_vec()
+    }
+
+    #[test]
+    fn closed_loop() {
+        let mut board = board();
+        let mut ring = around(Axial::new(0, 0), 5);
+        ring.push(ring[0]);
+        roads(&mut board, P0, &ring);
+        assert_eq!(board.longest_road(P0), 6);
+    }
+
+    #[test]
+    fn loop_with_tail() {
+        let mut board = board();
+        let mut ring = around(Axial::new(0, 0), 5);
+        let corners = ring.clone();
+        ring.push(ring[0]);
+        roads(&mut board, P0, &ring);
+        let mut tail = vec![corners[2]];
+        extend(&mut tail, 2, &corners);
+        roads(&mut board, P0, &tail);
+        assert_eq!(board.longest_road(P0), 8);
+    }
+
+    #[test]
+    fn fork_counts_two_longest_branches() {
+        let mut board = board();
+        let fork = VertexCoord::corners_of(Axial::new(0, 0))[0];
+        let mut visited = vec![fork];
+        for (branch, length) in fork.adjacent_vertices().into_iter().zip([3, 2, 1]) {
+            let mut path = vec![fork, branch];
+            extend(&mut path, length - 1, &visited);
+            visited.extend(path.iter().copied());
+            roads(&mut board, P0, &path);
+        }
+        assert_eq!(board.longest_road(P0), 5);
+    }
+
+    #[test]
+    fn opponent_settlement_cuts_trail() {
+        let mut board = board();
+        let mut path = around(Axial::new(0, 0), 5);
+        extend(&mut path, 1, &[]);
+        roads(&mut board, P0, &path);
+        assert_eq!(board.longest_road(P0), 6);
+
+        //on an end the trail can still finish there
+        settle(&mut board, P1, path[0]);
+        assert_eq!(board.longest_road(P0), 6);
+        settle(&mut board, P1, path[6]);
+        assert_eq!(board.longest_road(P0), 6);
+
+        settle(&mut board, P1, path[2]);
+        assert_eq!(board.longest_road(P0), 4);
+
+        //the player's own settlement doesn't cut anything
+        let mut board = self::board();
+        roads(&mut board, P0, &path);
+        settle(&mut board, P0, path[2]);
+        assert_eq!(board.longest_road(P0), 6);
+    }
+
+    fn world(board: BoardData) -> (World, Schedule) {
+        let mut world = World::new();
+        world.insert_resource(board);
+        world.insert_resource(Players::new(3));
+        let mut schedule = Schedule::new();
+        schedule.add_systems(award_longest_road);
+        (world, schedule)
+    }
+
+    fn holder(world: &World) -> Option<PlayerId> {
+        world
+            .resource::<Players>()
+            .list
+            .iter()
+            .find(|p| p.bonus_cards.contains(&BonusCard::LongestRoad))
+            .map(|p| p.id)
+    }
+
+    //far apart hexes on the outer ring so roads around them never touch
+    fn outer(i: usize) -> Axial {
+        HEX_OFFSETS[i] + HEX_OFFSETS[i]
+    }
+
+    #[test]
+    fn award_needs_five_segments() {
+        let (mut world, mut schedule) = world(board());
+        roads(
+            &mut world.resource_mut::<BoardData>(),
+            P0,
+            &around(outer(0), 4),
+        );
+        schedule.run(&mut world);
+        assert_eq!(holder(&world), None);
+
+        roads(
+            &mut world.resource_mut::<BoardData>(),
+            P0,
+            &around(outer(0), 5),
+        );
+        schedule.run(&mut world);
+        assert_eq!(holder(&world), Some(P0));
+    }
+
+    #[test]
+    fn award_tie_keeps_holder() {
+        let (mut world, mut schedule) = world(board());
+        roads(
+            &mut world.resource_mut::<BoardData>(),
+            P0,
+            &around(outer(0), 5),
+        );
+        schedule.run(&mut world);
+        assert_eq!(holder(&world), Some(P0));
+
+        roads(
+            &mut world.resource_mut::<BoardData>(),
+            P1,
+            &around(outer(2), 5),
+        );
+        schedule.run(&mut world);
+        assert_eq!(holder(&world), Some(P0));
+
+        let mut longer = around(outer(2), 5);
+        longer.push(longer[0]);
+        roads(&mut world.resource_mut::<BoardData>(), P1, &longer);
+        schedule.run(&mut world);
+        assert_eq!(holder(&world), Some(P1));
+    }
+
+    #[test]
+    fn award_set_aside_when_cut_leaves_a_tie() {
+        let (mut world, mut schedule) = world(board());
+        let center = around(Axial::new(0, 0), 5);
+        let mut long = center.clone();
+        extend(&mut long, 1, &[]);
+        {
+            let mut board = world.resource_mut::<BoardData>();
+            roads(&mut board, P0, &long);
+            roads(&mut board, P1, &around(outer(0), 5));
+            roads(&mut board, P2, &around(outer(3), 5));
+        }
+        schedule.run(&mut world);
+        assert_eq!(holder(&world), Some(P0));
+
+        settle(&mut world.resource_mut::<BoardData>(), P1, center[3]);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<BoardData>().longest_road(P0), 3);
+        assert_eq!(holder(&world), None);
+    }
+}
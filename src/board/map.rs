@@ -0,0 +1,511 @@
+use std::fmt;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap, HashSet},
+};
+use serde::Deserialize;
+
+use super::{
+    axial::Axial,
+    board_data::BoardData,
+    edge::EdgeCoord,
+    generate::{shuffle, GenerateOptions},
+    harbor::{Harbor, HarborKind, HARBORS},
+    hex,
+};
+use crate::loading::MapAssets;
+
+/// A board described in a `.map.ron` or `.map.json` file under `assets/maps`. Hexes are given as
+/// `(q, r)` where q runs east and r runs south east, one step per hex
+#[derive(Debug, Deserialize, TypeUuid, TypePath)]
+#[uuid = "6f1d3c2e-8a4b-4e57-9b0a-2c5d7e9f4a31"]
+pub struct Map {
+    pub hexes: Vec<MapHex>,
+    //left out, the standard harbors are spread evenly around the coast
+    #[serde(default)]
+    pub harbors: Option<Vec<MapHarbor>>,
+    //left out, the robber starts on the desert. maps without one have to say
+    #[serde(default)]
+    pub robber: Option<(i32, i32)>,
+    //deal the listed tiles, tokens and harbor kinds out at random instead of where they're written
+    #[serde(default)]
+    pub shuffle: bool,
+    //set by the loader when the file doesn't parse, the asset still loads so the game can report
+    //it and fall back instead of waiting on it forever
+    #[serde(skip)]
+    unreadable: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MapHex {
+    pub at: (i32, i32),
+    pub resource: hex::Resource,
+    //the desert has no number
+    #[serde(default)]
+    pub number: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MapHarbor {
+    //the land hex the harbor sits on
+    pub at: (i32, i32),
+    //which of its sides faces the water
+    pub side: Facing,
+    pub kind: HarborKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Facing {
+    NE,
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+}
+impl Facing {
+    fn edge(&self, hex: Axial) -> EdgeCoord {
+        //sides_of goes clockwise from the top right, the same order as the variants
+        EdgeCoord::sides_of(hex)[*self as usize]
+    }
+}
+
+//map coordinates step a whole hex at a time, the board's lattice is three times finer
+fn to_board(at: (i32, i32)) -> Axial {
+    Axial::from_hex_steps(Axial::new(at.0, at.1))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    NoHexes,
+    DuplicateHex((i32, i32)),
+    BadNumber {
+        at: (i32, i32),
+        resource: hex::Resource,
+        number: i32,
+    },
+    HarborOffMap((i32, i32)),
+    HarborInland {
+        at: (i32, i32),
+        side: Facing,
+    },
+    RobberOffMap((i32, i32)),
+    NoRobberStart,
+    DesertHarbor((i32, i32)),
+    SharedHarborEdge {
+        at: (i32, i32),
+        side: Facing,
+    },
+    Unreadable(String),
+}
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::NoHexes => write!(f, "the map has no hexes"),
+            MapError::DuplicateHex(at) => write!(f, "hex {:?} is listed more than once", at),
+            MapError::BadNumber {
+                at,
+                resource: hex::Resource::None,
+                number,
+            } => write!(f, "hex {:?} is desert and can't have number {}", at, number),
+            MapError::BadNumber {
+                at,
+                resource,
+                number,
+            } => write!(
+                f,
+                "hex {:?} is {:?} and needs a number from 2 to 12 other than 7, not {}",
+                at, resource, number
+            ),
+            MapError::HarborOffMap(at) => {
+                write!(f, "harbor on hex {:?} which isn't on the map", at)
+            },
+            MapError::HarborInland { at, side } => {
+                write!(
+                    f,
+                    "harbor on hex {:?} faces {:?} onto another hex",
+                    at, side
+                )
+            },
+            MapError::RobberOffMap(at) => {
+                write!(f, "robber starts on hex {:?} which isn't on the map", at)
+            },
+            MapError::NoRobberStart => {
+                write!(
+                    f,
+                    "the map has no desert, it has to say where the robber starts"
+                )
+            },
+            MapError::DesertHarbor(at) => {
+                write!(
+                    f,
+                    "harbor on hex {:?} trades desert, which isn't a resource",
+                    at
+                )
+            },
+            MapError::SharedHarborEdge { at, side } => write!(
+                f,
+                "harbor on hex {:?} facing {:?} is on the same edge as another harbor",
+                at, side
+            ),
+            MapError::Unreadable(e) => write!(f, "the map file can't be read, {}", e),
+        }
+    }
+}
+impl std::error::Error for MapError {}
+
+impl Map {
+    /// Parse a map file, one that doesn't parse comes back as a map that fails validation with
+    /// the reason
+    pub fn read(bytes: &[u8], is_json: bool) -> Self {
+        let map = if is_json {
+            serde_json::from_slice(bytes).map_err(|e| e.to_string())
+        } else {
+            ron::de::from_bytes(bytes).map_err(|e| e.to_string())
+        };
+        map.unwrap_or_else(Map::unreadable)
+    }
+
+    //a map from a file that didn't parse, it never validates
+    fn unreadable(error: String) -> Self {
+        Map {
+            hexes: Vec::new(),
+            harbors: None,
+            robber: None,
+            shuffle: false,
+            unreadable: Some(error),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), MapError> {
+        if let Some(e) = &self.unreadable {
+            return Err(MapError::Unreadable(e.clone()));
+        }
+        if self.hexes.is_empty() {
+            return Err(MapError::NoHexes);
+        }
+        let mut land = HashSet::new();
+        for h in self.hexes.iter() {
+            if !land.insert(h.at) {
+                return Err(MapError::DuplicateHex(h.at));
+            }
+            let valid = match h.resource {
+                hex::Resource::None => h.number == 0,
+                _ => (2..=12).contains(&h.number) && h.number != 7,
+            };
+            if !valid {
+                return Err(MapError::BadNumber {
+                    at: h.at,
+                    resource: h.resource,
+                    number: h.number,
+                });
+            }
+        }
+        let mut coast = HashSet::new();
+        for harbor in self.harbors.iter().flatten() {
+            if !land.contains(&harbor.at) {
+                return Err(MapError::HarborOffMap(harbor.at));
+            }
+            if harbor.kind == HarborKind::Special(hex::Resource::None) {
+                return Err(MapError::DesertHarbor(harbor.at));
+            }
+            let coords = harbor.side.edge(to_board(harbor.at));
+            if !coast.insert(coords) {
+                return Err(MapError::SharedHarborEdge {
+                    at: harbor.at,
+                    side: harbor.side,
+                });
+            }
+            let inland = coords
+                .hexes()
+                .iter()
+                .filter(|&&h| self.hexes.iter().any(|m| to_board(m.at) == h))
+                .count()
+                > 1;
+            if inland {
+                return Err(MapError::HarborInland {
+                    at: harbor.at,
+                    side: harbor.side,
+                });
+            }
+        }
+        if let Some(robber) = self.robber {
+            if !land.contains(&robber) {
+                return Err(MapError::RobberOffMap(robber));
+            }
+        } else if !self.hexes.iter().any(|h| h.resource == hex::Resource::None) {
+            //it would otherwise land on whatever hex is at the center and block it
+            return Err(MapError::NoRobberStart);
+        }
+        Ok(())
+    }
+}
+
+impl BoardData {
+    /// Lay out a board from a map file, the seed and options are only used for maps that shuffle
+    pub fn from_map(map: &Map, seed: u64, options: GenerateOptions) -> Result<Self, MapError> {
+        map.validate()?;
+        let positions: Vec<Axial> = map.hexes.iter().map(|h| to_board(h.at)).collect();
+
+        let mut board = if map.shuffle {
+            let tiles: Vec<hex::Resource> = map.hexes.iter().map(|h| h.resource).collect();
+            let numbers: Vec<i32> = map
+                .hexes
+                .iter()
+                .map(|h| h.number)
+                .filter(|&n| n != 0)
+                .collect();
+            //harbors written down keep their spots and only swap kinds, so none are dealt here
+            let kinds: Vec<HarborKind> = match map.harbors {
+                Some(_) => Vec::new(),
+                None => HARBORS.to_vec(),
+            };
+            BoardData::deal(seed, options, &positions, &tiles, &numbers, &kinds)
+        } else {
+            let hex_data: HashMap<Axial, (hex::Resource, i32)> = positions
+                .iter()
+                .zip(map.hexes.iter())
+                .map(|(&pos, h)| (pos, (h.resource, h.number)))
+                .collect();
+            BoardData::from_hex_data(hex_data)
+        };
+
+        if let Some(harbors) = &map.harbors {
+            let mut kinds: Vec<HarborKind> = harbors.iter().map(|h| h.kind).collect();
+            if map.shuffle {
+                shuffle(&mut random::default(seed), &mut kinds);
+            }
+            board.harbors = harbors
+                .iter()
+                .zip(kinds)
+                .map(|(h, kind)| {
+                    let hex = to_board(h.at);
+                    Harbor {
+                        coords: h.side.edge(hex),
+                        kind,
+                        hex,
+                    }
+                })
+                .collect();
+        }
+        if let Some(robber) = map.robber {
+            board.robber = to_board(robber);
+        }
+        Ok(board)
+    }
+}
+
+#[derive(Default)]
+pub struct MapLoader;
+impl AssetLoader for MapLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let is_json = load_context.path().extension().is_some_and(|e| e == "json");
+            let map = Map::read(bytes, is_json);
+            load_context.set_default_asset(LoadedAsset::new(map));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["map.ron", "map.json"]
+    }
+}
+
+//what shuffling maps are dealt with, a new one is drawn for every game
+#[derive(Resource)]
+pub struct BoardSeed(pub u64);
+
+//the board comes from the map file each time a game starts, a broken map is reported and the
+//standard generated board is played instead
+pub fn build_board_from_map(
+    mut board: ResMut<BoardData>,
+    seed: Res<BoardSeed>,
+    maps: Res<MapAssets>,
+    assets: Res<Assets<Map>>,
+) {
+    let options = GenerateOptions::balanced();
+    let from_map = assets
+        .get(&maps.board)
+        .map(|map| BoardData::from_map(map, seed.0, options));
+    *board = match from_map {
+        Some(Ok(b)) => b,
+        Some(Err(e)) => {
+            error!("can't play the map, {}", e);
+            BoardData::generate(seed.0, options)
+        },
+        None => BoardData::generate(seed.0, options),
+    };
+    info!("board fairness {:.2}", board.fairness());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(ron: &str) -> Result<(), MapError> {
+        Map::read(ron.as_bytes(), false).validate()
+    }
+
+    #[test]
+    fn shipped_map() {
+        let map = Map::read(include_bytes!("../../assets/maps/board.map.ron"), false);
+        assert_eq!(map.validate(), Ok(()));
+        let board = BoardData::from_map(&map, 1, GenerateOptions::balanced()).unwrap();
+        assert_eq!(board.hexes.len(), 19);
+        assert_eq!(board.harbors.len(), HARBORS.len());
+        assert_eq!(
+            board.hexes[&board.robber].resource_type,
+            hex::Resource::None
+        );
+    }
+
+    #[test]
+    fn json_map() {
+        let json = r#"{
+            "hexes": [{"at": [0, 0], "resource": "Wood", "number": 8}],
+            "harbors": [{"at": [0, 0], "side": "E", "kind": "Generic"}],
+            "robber": [0, 0]
+        }"#;
+        let map = Map::read(json.as_bytes(), true);
+        assert_eq!(map.validate(), Ok(()));
+        assert_eq!(map.hexes.len(), 1);
+    }
+
+    #[test]
+    fn unreadable() {
+        assert!(matches!(
+            check("(hexes: [(at: (0, 0), resource: Wood"),
+            Err(MapError::Unreadable(_))
+        ));
+        assert!(matches!(
+            Map::read(b"{\"hexes\": [", true).validate(),
+            Err(MapError::Unreadable(_))
+        ));
+    }
+
+    #[test]
+    fn no_hexes() {
+        assert_eq!(check("(hexes: [])"), Err(MapError::NoHexes));
+    }
+
+    #[test]
+    fn duplicate_hex() {
+        assert_eq!(
+            check(
+                "(hexes: [(at: (1, 0), resource: Desert), (at: (1, 0), resource: Ore, number: 5)])"
+            ),
+            Err(MapError::DuplicateHex((1, 0)))
+        );
+    }
+
+    #[test]
+    fn bad_number() {
+        let bad = |at, resource, number| {
+            Err(MapError::BadNumber {
+                at,
+                resource,
+                number,
+            })
+        };
+        assert_eq!(
+            check("(hexes: [(at: (0, 0), resource: Desert, number: 6)])"),
+            bad((0, 0), hex::Resource::None, 6)
+        );
+        assert_eq!(
+            check("(hexes: [(at: (0, 0), resource: Wood, number: 7)])"),
+            bad((0, 0), hex::Resource::Wood, 7)
+        );
+        assert_eq!(
+            check("(hexes: [(at: (0, 0), resource: Ore)])"),
+            bad((0, 0), hex::Resource::Ore, 0)
+        );
+    }
+
+    #[test]
+    fn harbor_off_map() {
+        assert_eq!(
+            check(
+                "(hexes: [(at: (0, 0), resource: Desert)],
+                  harbors: Some([(at: (2, 2), side: E, kind: Generic)]))"
+            ),
+            Err(MapError::HarborOffMap((2, 2)))
+        );
+    }
+
+    #[test]
+    fn harbor_inland() {
+        let map = |side| {
+            format!(
+                "(hexes: [(at: (0, 0), resource: Desert), (at: (1, 0), resource: Ore, number: 5)],
+                  harbors: Some([(at: (0, 0), side: {}, kind: Generic)]))",
+                side
+            )
+        };
+        assert_eq!(
+            check(&map("E")),
+            Err(MapError::HarborInland {
+                at: (0, 0),
+                side: Facing::E,
+            })
+        );
+        assert_eq!(check(&map("W")), Ok(()));
+    }
+
+    #[test]
+    fn desert_harbor() {
+        assert_eq!(
+            check(
+                "(hexes: [(at: (0, 0), resource: Desert)],
+                  harbors: Some([(at: (0, 0), side: E, kind: Special(Desert))]))"
+            ),
+            Err(MapError::DesertHarbor((0, 0)))
+        );
+    }
+
+    #[test]
+    fn shared_harbor_edge() {
+        assert_eq!(
+            check(
+                "(hexes: [(at: (0, 0), resource: Desert)],
+                  harbors: Some([
+                      (at: (0, 0), side: NE, kind: Generic),
+                      (at: (0, 0), side: NE, kind: Special(Ore)),
+                  ]))"
+            ),
+            Err(MapError::SharedHarborEdge {
+                at: (0, 0),
+                side: Facing::NE,
+            })
+        );
+    }
+
+    #[test]
+    fn robber_off_map() {
+        assert_eq!(
+            check("(hexes: [(at: (0, 0), resource: Desert)], robber: Some((0, 1)))"),
+            Err(MapError::RobberOffMap((0, 1)))
+        );
+    }
+
+    #[test]
+    fn no_robber_start() {
+        assert_eq!(
+            check("(hexes: [(at: (0, 0), resource: Wood, number: 8)])"),
+            Err(MapError::NoRobberStart)
+        );
+        let map = Map::read(
+            b"(hexes: [(at: (0, 0), resource: Wood, number: 8), (at: (1, 0), resource: Ore, number: 5)], robber: Some((1, 0)))",
+            false,
+        );
+        let board = BoardData::from_map(&map, 1, GenerateOptions::default()).unwrap();
+        assert_eq!(board.robber, Axial::from_hex_steps(Axial::new(1, 0)));
+    }
+}
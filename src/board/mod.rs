@@ -1,8 +1,25 @@
-mod axial;
+pub mod adjacency;
+pub mod axial;
+pub mod bank;
 pub mod board_data;
+pub mod cost;
+pub mod discard;
 pub mod edge;
+pub mod generate;
+pub mod harbor;
 pub mod hex;
+pub mod knight;
+pub mod log;
+pub mod longest_road;
+pub mod map;
 pub mod pickable_pos;
+pub mod progress;
+pub mod resource_cards;
+pub mod robber;
+pub mod roll;
+pub mod setup;
+pub mod tile;
+pub mod trade;
 pub mod vertex;
 use std::time::Duration;
 
@@ -11,17 +28,48 @@ use bevy_tweening::{
     lens::TransformScaleLens, Animator, EaseFunction, RepeatCount, RepeatStrategy, Tween,
 };
 use leafwing_input_manager::{
-    prelude::{ActionState, InputMap},
+    prelude::{ActionState, InputManagerPlugin, InputMap},
     Actionlike, InputManagerBundle,
 };
 
-use self::{board_data::BoardData, pickable_pos::PickablePos};
+use self::{
+    bank::{bank_trade, end_bank_trade, open_bank_trade, Bank, BankTrade},
+    board_data::BoardData,
+    cost::Build,
+    discard::{discard_cards, owe_discards, Discards},
+    edge::{Edge, EdgeCoord, EdgeType},
+    generate::{GenerateOptions, BOARD_SEED},
+    hex::BuildType,
+    knight::{play_knight, position_played_cards, spawn_played_areas, PlayedCards},
+    log::GameLog,
+    longest_road::award_longest_road,
+    map::{build_board_from_map, BoardSeed},
+    pickable_pos::{PickTarget, PickablePos, PickerSelected},
+    progress::{
+        cancel_progress_card, despawn_resource_chooser, monopoly, play_progress_card,
+        road_building, settle_progress_card, spawn_resource_chooser, start_road_building,
+        year_of_plenty, CardStage, ProgressCard,
+    },
+    resource_cards::{despawn_spent_cards, sync_resource_cards, ResourcesCollected, SpentCard},
+    robber::{move_robber, spawn_robber, spawn_robber_pickers, Robber},
+    roll::{
+        end_turn, produce_resources, roll_dice, spawn_turn_input, Dice, DiceRolled, TurnAction,
+        TurnEnded, DICE_SEED,
+    },
+    setup::{advance_setup, Setup},
+    tile::{sync_board, HarborSprite, Tile},
+    trade::{
+        answer_trades, clear_trades, complete_trades, propose_trades, AcceptTrade, CompleteTrade,
+        CounterTrade, ProposeTrade, RejectTrade, TradeOffers,
+    },
+    vertex::VertexCoord,
+};
 use crate::{
     camera::BoardCamera,
-    deck::DeckAction,
+    deck::{sync_dev_cards, DeckAction},
     hand::HandAction,
     loading::TextureAssets,
-    utils::{calculate_rotated_bounds, point_in_polygon},
+    player::{PlayerId, Players},
     AppState,
 };
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
@@ -29,20 +77,148 @@ pub enum GameState {
     #[default]
     Setup,
     Roll,
+    Robber,
     Turn,
 }
+//steps inside GameState::Roll, discarding blocks the robber until everyone is done
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum RollStage {
+    #[default]
+    Dice,
+    Discard,
+}
 #[derive(Component)]
 pub struct Board;
+#[derive(Component)]
+pub struct Road;
+#[derive(Component)]
+pub struct Building(pub VertexCoord);
+
+#[derive(Event)]
+pub struct SettlementPlaced {
+    pub pos: VertexCoord,
+    pub owner: PlayerId,
+}
+#[derive(Event)]
+pub struct RoadPlaced;
 
 pub struct BoardPlugin;
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnEnter(AppState::Playing),
-            (spawn_board, spawn_settlement_coord_pickers),
-        )
-        .add_systems(Update, select_picker.run_if(in_state(AppState::Playing)))
-        .insert_resource(BoardData::new());
+        app.add_state::<GameState>()
+            .add_state::<RollStage>()
+            .add_state::<CardStage>()
+            .add_event::<DiceRolled>()
+            .add_event::<TurnEnded>()
+            .add_event::<PickerSelected>()
+            .add_event::<SettlementPlaced>()
+            .add_event::<RoadPlaced>()
+            .add_event::<ResourcesCollected>()
+            .add_event::<ProposeTrade>()
+            .add_event::<CounterTrade>()
+            .add_event::<AcceptTrade>()
+            .add_event::<RejectTrade>()
+            .add_event::<CompleteTrade>()
+            .add_plugins(InputManagerPlugin::<TurnAction>::default())
+            .add_systems(
+                OnEnter(AppState::Playing),
+                (
+                    build_board_from_map,
+                    (
+                        spawn_robber,
+                        spawn_settlement_coord_pickers,
+                        spawn_turn_input,
+                        spawn_played_areas,
+                    ),
+                )
+                    .chain(),
+            )
+            .add_systems(OnExit(AppState::Playing), despawn_board)
+            .add_systems(
+                Update,
+                (
+                    select_picker,
+                    end_bank_trade,
+                    clear_trades,
+                    clear_pickers,
+                    sync_board,
+                    position_played_cards,
+                    //both move cards in and out of the hand, so the second has to see the first's
+                    (sync_resource_cards, apply_deferred, sync_dev_cards).chain(),
+                    despawn_spent_cards,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    advance_setup.run_if(in_state(GameState::Setup)),
+                    roll_dice
+                        .run_if(in_state(GameState::Roll))
+                        .run_if(in_state(RollStage::Dice)),
+                    discard_cards
+                        .after(sync_resource_cards)
+                        .run_if(in_state(RollStage::Discard)),
+                    move_robber.run_if(in_state(GameState::Robber)),
+                    (
+                        end_turn,
+                        start_settlement,
+                        start_road,
+                        start_city,
+                        play_knight,
+                        play_progress_card,
+                        open_bank_trade,
+                        bank_trade.run_if(resource_exists::<BankTrade>()),
+                    )
+                        .run_if(in_state(GameState::Turn))
+                        .run_if(in_state(CardStage::Idle)),
+                    place_settlement
+                        .run_if(in_state(GameState::Setup).or_else(in_state(GameState::Turn))),
+                    place_road.run_if(
+                        in_state(GameState::Setup)
+                            .or_else(in_state(GameState::Turn))
+                            .or_else(in_state(CardStage::RoadBuilding)),
+                    ),
+                    place_city.run_if(in_state(GameState::Turn)),
+                    produce_resources,
+                    award_longest_road,
+                )
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (
+                    cancel_progress_card.run_if(resource_exists::<ProgressCard>()),
+                    road_building.run_if(in_state(CardStage::RoadBuilding)),
+                    year_of_plenty.run_if(in_state(CardStage::YearOfPlenty)),
+                    monopoly.run_if(in_state(CardStage::Monopoly)),
+                )
+                    .run_if(not(in_state(CardStage::Idle)))
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnEnter(CardStage::Idle), settle_progress_card)
+            .add_systems(OnEnter(CardStage::RoadBuilding), start_road_building)
+            .add_systems(OnEnter(CardStage::YearOfPlenty), spawn_resource_chooser)
+            .add_systems(OnExit(CardStage::YearOfPlenty), despawn_resource_chooser)
+            .add_systems(OnEnter(CardStage::Monopoly), spawn_resource_chooser)
+            .add_systems(OnExit(CardStage::Monopoly), despawn_resource_chooser)
+            .add_systems(
+                Update,
+                (propose_trades, answer_trades, complete_trades)
+                    .chain()
+                    .run_if(in_state(GameState::Turn))
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnEnter(GameState::Robber), spawn_robber_pickers)
+            .add_systems(OnEnter(RollStage::Discard), owe_discards)
+            .insert_resource(BoardData::generate(BOARD_SEED, GenerateOptions::balanced()))
+            .insert_resource(BoardSeed(BOARD_SEED))
+            .insert_resource(Dice::new(DICE_SEED))
+            .init_resource::<Bank>()
+            .init_resource::<Discards>()
+            .init_resource::<GameLog>()
+            .init_resource::<TradeOffers>()
+            .init_resource::<Setup>();
         // .add_systems(
         //     Update,
         //     (position_cards, draw_card).run_if(in_state(GameState::Playing)),
@@ -50,69 +226,397 @@ impl Plugin for BoardPlugin {
         // .add_plugins(InputManagerPlugin::<DeckAction>::default());
     }
 }
-pub fn spawn_board(board: Res<BoardData>, mut cmd: Commands, textures: Res<TextureAssets>) {
-    for pos in board.hexes.keys() {
-        let t = match board.hexes[pos].resource_type {
-            hex::Resource::None => textures.desert_tile.clone(),
-            hex::Resource::Ore => textures.ore_tile.clone(),
-            hex::Resource::Wheat => textures.wheat_tile.clone(),
-            hex::Resource::Sheep => textures.sheep_tile.clone(),
-            hex::Resource::Brick => textures.brick_tile.clone(),
-            hex::Resource::Wood => textures.wood_tile.clone(),
-        };
+//everything on the board is respawned when a new game starts
+fn despawn_board(
+    mut cmd: Commands,
+    q: Query<
+        Entity,
+        Or<(
+            With<Tile>,
+            With<HarborSprite>,
+            With<Building>,
+            With<Road>,
+            With<Robber>,
+            With<PickablePos>,
+            With<PlayedCards>,
+            With<SpentCard>,
+            With<ActionState<TurnAction>>,
+        )>,
+    >,
+) {
+    for entity in q.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+pub fn spawn_settlement_coord_pickers(
+    board: Res<BoardData>,
+    mut cmd: Commands,
+    textures: Res<TextureAssets>,
+) {
+    spawn_settlement_pickers(&mut cmd, &textures, &board);
+}
+pub fn spawn_settlement_pickers(cmd: &mut Commands, textures: &TextureAssets, board: &BoardData) {
+    let coords = board
+        .get_valid_settlement_coords()
+        .into_iter()
+        .map(|c| c.pos);
+    spawn_vertex_pickers(cmd, textures, coords, PickablePos::new);
+}
+//a pulsing picker on each corner, `picker` says what picking it does
+fn spawn_vertex_pickers(
+    cmd: &mut Commands,
+    textures: &TextureAssets,
+    coords: impl IntoIterator<Item = VertexCoord>,
+    picker: fn(VertexCoord) -> PickablePos,
+) {
+    for pos in coords {
         cmd.spawn((
             SpriteBundle {
-                texture: t,
+                texture: textures.pickable_pos.clone(),
                 transform: Transform {
-                    translation: pos.get_cartesian(),
+                    translation: pos.get_cartesian() + Vec3::Z,
                     ..default()
                 },
                 ..default()
             },
+            picker(pos),
+            Animator::new(picker_tween()),
             RenderLayers::layer(1),
         ));
     }
 }
-pub fn spawn_settlement_coord_pickers(
-    board: Res<BoardData>,
-    mut cmd: Commands,
-    textures: Res<TextureAssets>,
+pub fn spawn_road_coord_pickers(
+    cmd: &mut Commands,
+    textures: &TextureAssets,
+    board: &BoardData,
+    player: PlayerId,
 ) {
-    for c in board.get_valid_settlement_coords() {
+    spawn_edge_pickers(cmd, textures, board.get_valid_road_coords(player));
+}
+pub fn spawn_edge_pickers(cmd: &mut Commands, textures: &TextureAssets, edges: Vec<&Edge>) {
+    for e in edges {
+        let (a, b) = e.path_coords.ends();
         let entity = cmd
             .spawn((
                 SpriteBundle {
                     texture: textures.pickable_pos.clone(),
                     transform: Transform {
-                        translation: c.pos.get_cartesian() + Vec3::Z,
+                        translation: (a.get_cartesian() + b.get_cartesian()) / 2. + Vec3::Z,
                         ..default()
                     },
                     ..default()
                 },
-                PickablePos::new(c.pos),
+                PickablePos::edge(e.path_coords),
                 RenderLayers::layer(1),
             ))
             .id();
-        let tween = Tween::new(
-            EaseFunction::QuadraticInOut,
-            Duration::from_millis(2000),
-            TransformScaleLens {
-                start: Vec3::new(0.75, 0.75, 1.),
-                end: Vec3::new(1.25, 1.25, 1.),
+        cmd.entity(entity).insert(Animator::new(picker_tween()));
+    }
+}
+//starting another build replaces whatever was being picked before
+fn despawn_pickers(cmd: &mut Commands, pickers: &Query<Entity, With<PickablePos>>) {
+    for entity in pickers.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+//nothing left to pick from one turn carries over to the next player
+pub fn clear_pickers(
+    mut cmd: Commands,
+    mut ended: EventReader<TurnEnded>,
+    pickers: Query<Entity, With<PickablePos>>,
+) {
+    if ended.iter().count() > 0 {
+        despawn_pickers(&mut cmd, &pickers);
+    }
+}
+fn picker_tween() -> Tween<Transform> {
+    Tween::new(
+        EaseFunction::QuadraticInOut,
+        Duration::from_millis(2000),
+        TransformScaleLens {
+            start: Vec3::new(0.75, 0.75, 1.),
+            end: Vec3::new(1.25, 1.25, 1.),
+        },
+    )
+    .with_repeat_count(RepeatCount::Infinite)
+    .with_repeat_strategy(RepeatStrategy::MirroredRepeat)
+}
+
+pub fn start_settlement(
+    mut cmd: Commands,
+    q: Query<&ActionState<TurnAction>>,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    textures: Res<TextureAssets>,
+    pickers: Query<Entity, With<PickablePos>>,
+) {
+    if !q.single().just_pressed(TurnAction::BuildSettlement) {
+        return;
+    }
+    if let Err(e) = players.active().can_afford(Build::Settlement) {
+        info!("{} {}", players.active().name, e);
+        return;
+    }
+    despawn_pickers(&mut cmd, &pickers);
+    let coords = board
+        .get_connected_settlement_coords(players.active)
+        .into_iter()
+        .map(|c| c.pos);
+    spawn_vertex_pickers(&mut cmd, &textures, coords, PickablePos::new);
+}
+
+pub fn start_road(
+    mut cmd: Commands,
+    q: Query<&ActionState<TurnAction>>,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    textures: Res<TextureAssets>,
+    pickers: Query<Entity, With<PickablePos>>,
+) {
+    if !q.single().just_pressed(TurnAction::BuildRoad) {
+        return;
+    }
+    if let Err(e) = players.active().can_afford(Build::Road) {
+        info!("{} {}", players.active().name, e);
+        return;
+    }
+    despawn_pickers(&mut cmd, &pickers);
+    spawn_road_coord_pickers(&mut cmd, &textures, &board, players.active);
+}
+
+pub fn start_city(
+    mut cmd: Commands,
+    q: Query<&ActionState<TurnAction>>,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    textures: Res<TextureAssets>,
+    pickers: Query<Entity, With<PickablePos>>,
+) {
+    if !q.single().just_pressed(TurnAction::UpgradeCity) {
+        return;
+    }
+    let player = players.active();
+    if let Err(e) = player.can_afford(Build::City) {
+        info!("{} {}", player.name, e);
+        return;
+    }
+    despawn_pickers(&mut cmd, &pickers);
+    let coords = board
+        .get_upgradable_settlements(player.id)
+        .into_iter()
+        .map(|v| v.pos);
+    spawn_vertex_pickers(&mut cmd, &textures, coords, PickablePos::upgrade);
+}
+
+pub fn place_city(
+    mut cmd: Commands,
+    mut board: ResMut<BoardData>,
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
+    mut picked: EventReader<PickerSelected>,
+    pickers: Query<(Entity, &PickablePos)>,
+    buildings: Query<(Entity, &Building)>,
+) {
+    for p in picked.iter() {
+        let PickTarget::Upgrade(pos) = p.target else {
+            continue;
+        };
+        for (entity, picker) in pickers.iter() {
+            if let PickTarget::Upgrade(_) = picker.target() {
+                cmd.entity(entity).despawn_recursive();
+            }
+        }
+        let player = players.active_mut();
+        //only the active player's own settlements can be upgraded
+        if !board
+            .vertices
+            .get(&pos)
+            .is_some_and(|v| v.owner == Some(player.id) && v.build_type == BuildType::Settlement)
+        {
+            continue;
+        }
+        if let Err(e) = player.pay(Build::City, &mut bank) {
+            info!("{} {}", player.name, e);
+            return;
+        }
+        if let Some(v) = board.vertices.get_mut(&pos) {
+            v.build_type = BuildType::City;
+        }
+        for (entity, building) in buildings.iter() {
+            if building.0 == pos {
+                cmd.entity(entity).despawn_recursive();
+            }
+        }
+        spawn_city(&mut cmd, pos, player.color);
+        return;
+    }
+}
+
+pub fn place_settlement(
+    mut cmd: Commands,
+    mut board: ResMut<BoardData>,
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
+    state: Res<State<GameState>>,
+    mut picked: EventReader<PickerSelected>,
+    pickers: Query<(Entity, &PickablePos)>,
+    mut placed_writer: EventWriter<SettlementPlaced>,
+) {
+    for p in picked.iter() {
+        let PickTarget::Vertex(pos) = p.target else {
+            continue;
+        };
+        //the distance rule changes with every settlement so pickers are respawned from scratch
+        for (entity, picker) in pickers.iter() {
+            if let PickTarget::Vertex(_) = picker.target() {
+                cmd.entity(entity).despawn_recursive();
+            }
+        }
+        let player = players.active_mut();
+        //settlements placed during setup are free
+        let paid = match state.get() {
+            GameState::Setup => player.take_piece(Build::Settlement),
+            _ => player.pay(Build::Settlement, &mut bank),
+        };
+        if let Err(e) = paid {
+            info!("{} {}", player.name, e);
+            return;
+        }
+        if let Some(v) = board.vertices.get_mut(&pos) {
+            v.build_type = BuildType::Settlement;
+            v.owner = Some(player.id);
+        }
+        spawn_building(&mut cmd, pos, player.color);
+
+        placed_writer.send(SettlementPlaced {
+            pos,
+            owner: players.active,
+        });
+        return;
+    }
+}
+
+pub fn place_road(
+    mut cmd: Commands,
+    mut board: ResMut<BoardData>,
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
+    state: Res<State<GameState>>,
+    card_stage: Res<State<CardStage>>,
+    mut picked: EventReader<PickerSelected>,
+    pickers: Query<(Entity, &PickablePos)>,
+    mut placed_writer: EventWriter<RoadPlaced>,
+) {
+    for p in picked.iter() {
+        let PickTarget::Edge(coords) = p.target else {
+            continue;
+        };
+        for (entity, picker) in pickers.iter() {
+            if let PickTarget::Edge(_) = picker.target() {
+                cmd.entity(entity).despawn_recursive();
+            }
+        }
+        let player = players.active_mut();
+        //roads are free during setup and from a road building card
+        let free = *state.get() == GameState::Setup || *card_stage.get() == CardStage::RoadBuilding;
+        let paid = if free {
+            player.take_piece(Build::Road)
+        } else {
+            player.pay(Build::Road, &mut bank)
+        };
+        if let Err(e) = paid {
+            info!("{} {}", player.name, e);
+            return;
+        }
+        if let Some(edge) = board.edges.get_mut(&coords) {
+            edge.path_type = EdgeType::Road;
+            edge.owner = Some(player.id);
+        }
+        spawn_road(&mut cmd, coords, player.color);
+
+        placed_writer.send(RoadPlaced);
+        return;
+    }
+}
+
+fn spawn_building(cmd: &mut Commands, pos: VertexCoord, color: Color) {
+    cmd.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::new(24., 24.)),
+                ..default()
+            },
+            transform: Transform {
+                translation: pos.get_cartesian() + Vec3::Z * 0.75,
+                ..default()
             },
+            ..default()
+        },
+        Building(pos),
+        RenderLayers::layer(1),
+    ));
+}
+
+//a city is a wide base with a tower on the left, so it reads apart from a settlement's square
+fn spawn_city(cmd: &mut Commands, pos: VertexCoord, color: Color) {
+    let part = |size: Vec2, offset: Vec3| {
+        (
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(offset),
+                ..default()
+            },
+            RenderLayers::layer(1),
         )
-        .with_repeat_count(RepeatCount::Infinite)
-        .with_repeat_strategy(RepeatStrategy::MirroredRepeat);
-        cmd.entity(entity).insert(Animator::new(tween));
-    }
+    };
+    cmd.spawn((
+        part(
+            Vec2::new(36., 20.),
+            pos.get_cartesian() + Vec3::new(0., -6., 0.75),
+        ),
+        Building(pos),
+    ))
+    .with_children(|parent| {
+        parent.spawn(part(Vec2::new(16., 16.), Vec3::new(-10., 18., 0.)));
+    });
+}
+
+fn spawn_road(cmd: &mut Commands, coords: EdgeCoord, color: Color) {
+    let (a, b) = coords.ends();
+    let (a, b) = (a.get_cartesian(), b.get_cartesian());
+    let d = b - a;
+    cmd.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(Vec2::new(d.length() - 20., 8.)),
+                ..default()
+            },
+            transform: Transform {
+                translation: (a + b) / 2. + Vec3::Z * 0.5,
+                rotation: Quat::from_rotation_z(d.y.atan2(d.x)),
+                ..default()
+            },
+            ..default()
+        },
+        Road,
+        RenderLayers::layer(1),
+    ));
 }
 
 pub fn select_picker(
     mut cmd: Commands,
+    board: Res<BoardData>,
     mut q: Query<&ActionState<HandAction>>,
-    mut pickers: Query<(Entity, &PickablePos, &Transform)>,
+    mut pickers: Query<(Entity, &mut PickablePos)>,
     mut q_window: Query<&Window, With<PrimaryWindow>>,
     mut q_camera: Query<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    mut picked_writer: EventWriter<PickerSelected>,
 ) {
     let a = q.single();
 
@@ -120,16 +624,25 @@ pub fn select_picker(
         if let Some(pos) = q_window.single().cursor_position() {
             let (camera, camera_transform) = q_camera.single();
             if let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, pos) {
-                for (entity, picker, transform) in pickers.iter() {
-                    let half_width = 18.;
-                    let half_height = 18.;
-                    let rotated_bounds =
-                        calculate_rotated_bounds(transform, half_width, half_height);
+                for (entity, mut picker) in pickers.iter_mut() {
+                    if picker.is_selected() {
+                        continue;
+                    }
+                    let hit = match picker.target() {
+                        PickTarget::Vertex(a) | PickTarget::Upgrade(a) => {
+                            board.vertex_at(world_pos) == Some(a)
+                        },
+                        PickTarget::Hex(a) => board.hex_at(world_pos) == Some(a),
+                        PickTarget::Edge(coords) => board.edge_at(world_pos) == Some(coords),
+                    };
 
-                    if point_in_polygon(world_pos, &rotated_bounds) {
+                    if hit {
                         cmd.entity(entity).remove::<Animator<Transform>>();
-                        // max_ord = Some(ord.0);
-                        // hovered_entity = Some(entity);
+                        picker.select();
+                        picked_writer.send(PickerSelected {
+                            target: picker.target(),
+                        });
+                        return;
                     }
                 }
             }
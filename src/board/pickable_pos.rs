@@ -1,15 +1,57 @@
-use bevy::prelude::Component;
+use bevy::prelude::{Component, Event};
 
-use super::axial::Axial;
+use super::{axial::Axial, edge::EdgeCoord, vertex::VertexCoord};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickTarget {
+    Vertex(VertexCoord),
+    Hex(Axial),
+    Edge(EdgeCoord),
+    Upgrade(VertexCoord),
+}
 
 #[derive(Component)]
 pub struct PickablePos {
-    a: Axial,
+    target: PickTarget,
     selected: bool,
 }
 impl PickablePos {
-    pub fn new(a: Axial) -> Self {
-        Self { a, selected: false }
+    pub fn new(v: VertexCoord) -> Self {
+        Self {
+            target: PickTarget::Vertex(v),
+            selected: false,
+        }
+    }
+    pub fn hex(a: Axial) -> Self {
+        Self {
+            target: PickTarget::Hex(a),
+            selected: false,
+        }
+    }
+    pub fn edge(coords: EdgeCoord) -> Self {
+        Self {
+            target: PickTarget::Edge(coords),
+            selected: false,
+        }
+    }
+    pub fn upgrade(v: VertexCoord) -> Self {
+        Self {
+            target: PickTarget::Upgrade(v),
+            selected: false,
+        }
     }
-    // add code here
+    pub fn target(&self) -> PickTarget {
+        self.target
+    }
+    pub fn select(&mut self) {
+        self.selected = true;
+    }
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+}
+
+#[derive(Event)]
+pub struct PickerSelected {
+    pub target: PickTarget,
 }
@@ -0,0 +1,286 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+
+use super::{
+    bank::Bank,
+    board_data::BoardData,
+    cost::Build,
+    despawn_pickers,
+    hex::{self, resource_tint},
+    log::{GameAction, GameLog},
+    pickable_pos::PickablePos,
+    roll::TurnAction,
+    spawn_road_coord_pickers, RoadPlaced,
+};
+use crate::{
+    card::Ordinal,
+    deck::{DevCard, DevelopmentCard},
+    hand::{remove_from_hand, CardPlayed, Hand},
+    loading::TextureAssets,
+    player::Players,
+};
+
+//steps inside GameState::Turn while a progress card is being resolved
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum CardStage {
+    #[default]
+    Idle,
+    RoadBuilding,
+    YearOfPlenty,
+    Monopoly,
+}
+
+//the card being played, it stays in the hand until it is done so a cancel can hand it back
+#[derive(Resource)]
+pub struct ProgressCard {
+    card: Entity,
+    picked: Vec<hex::Resource>,
+    roads: u32,
+    done: bool,
+}
+impl ProgressCard {
+    //once anything has happened the card can't be taken back
+    fn started(&self) -> bool {
+        self.roads > 0 || !self.picked.is_empty()
+    }
+}
+
+#[derive(Component)]
+pub struct ResourceChooser;
+#[derive(Component)]
+pub struct ResourceChoice(pub hex::Resource);
+
+pub fn play_progress_card(
+    mut cmd: Commands,
+    players: Res<Players>,
+    mut played: EventReader<CardPlayed>,
+    q_dev: Query<&DevelopmentCard>,
+    mut next_stage: ResMut<NextState<CardStage>>,
+) {
+    for e in played.iter() {
+        let Ok(dev_card) = q_dev.get(e.card) else {
+            continue;
+        };
+        let stage = match dev_card.kind {
+            DevCard::RoadBuilding => CardStage::RoadBuilding,
+            DevCard::YearOfPlenty => CardStage::YearOfPlenty,
+            DevCard::Monopoly => CardStage::Monopoly,
+            DevCard::Knight | DevCard::VictoryPoint => continue,
+        };
+        if !dev_card.playable(&players) {
+            info!("that card can't be played yet");
+            continue;
+        }
+        cmd.insert_resource(ProgressCard {
+            card: e.card,
+            picked: Vec::new(),
+            roads: 0,
+            done: false,
+        });
+        next_stage.set(stage);
+        return;
+    }
+}
+
+pub fn cancel_progress_card(
+    mut cmd: Commands,
+    q: Query<&ActionState<TurnAction>>,
+    progress: Res<ProgressCard>,
+    pickers: Query<Entity, With<PickablePos>>,
+    mut next_stage: ResMut<NextState<CardStage>>,
+) {
+    if !q.single().just_pressed(TurnAction::Cancel) {
+        return;
+    }
+    if progress.started() {
+        info!("this card is already being resolved");
+        return;
+    }
+    despawn_pickers(&mut cmd, &pickers);
+    next_stage.set(CardStage::Idle);
+}
+
+//a played card is thrown away, a cancelled one just goes back to being a card in the hand
+pub fn settle_progress_card(
+    mut cmd: Commands,
+    progress: Option<Res<ProgressCard>>,
+    mut q_hand: Query<(&mut Hand, &Children)>,
+    mut q_ordinals: Query<&mut Ordinal>,
+) {
+    let Some(progress) = progress else {
+        return;
+    };
+    if progress.done {
+        let (mut hand, children) = q_hand.single_mut();
+        remove_from_hand(&mut hand, children, &[progress.card], &mut q_ordinals);
+        cmd.entity(progress.card).despawn_recursive();
+    }
+    cmd.remove_resource::<ProgressCard>();
+}
+
+pub fn start_road_building(
+    mut cmd: Commands,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    textures: Res<TextureAssets>,
+    pickers: Query<Entity, With<PickablePos>>,
+    mut next_stage: ResMut<NextState<CardStage>>,
+) {
+    //settlement and upgrade pickers left open would still buy pieces during the card
+    despawn_pickers(&mut cmd, &pickers);
+    if players.active().has_piece(Build::Road).is_err()
+        || board.get_valid_road_coords(players.active).is_empty()
+    {
+        info!("{} has nowhere to build a road", players.active().name);
+        next_stage.set(CardStage::Idle);
+        return;
+    }
+    spawn_road_coord_pickers(&mut cmd, &textures, &board, players.active);
+}
+
+//two roads for free, fewer if the player runs out of pieces or places to put them
+pub fn road_building(
+    mut cmd: Commands,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    textures: Res<TextureAssets>,
+    mut log: ResMut<GameLog>,
+    mut progress: ResMut<ProgressCard>,
+    mut placed: EventReader<RoadPlaced>,
+    mut next_stage: ResMut<NextState<CardStage>>,
+) {
+    for _ in placed.iter() {
+        progress.roads += 1;
+        if progress.roads < 2
+            && players.active().has_piece(Build::Road).is_ok()
+            && !board.get_valid_road_coords(players.active).is_empty()
+        {
+            spawn_road_coord_pickers(&mut cmd, &textures, &board, players.active);
+        } else {
+            log.record(GameAction::RoadBuilding {
+                player: players.active,
+            });
+            progress.done = true;
+            next_stage.set(CardStage::Idle);
+            return;
+        }
+    }
+}
+
+pub fn spawn_resource_chooser(mut cmd: Commands) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(24.),
+                justify_content: JustifyContent::Center,
+                column_gap: Val::Px(12.),
+                ..default()
+            },
+            ..default()
+        },
+        ResourceChooser,
+    ))
+    .with_children(|parent| {
+        for resource in hex::RESOURCES {
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            padding: UiRect::all(Val::Px(12.)),
+                            ..default()
+                        },
+                        background_color: resource_tint(resource).into(),
+                        ..default()
+                    },
+                    ResourceChoice(resource),
+                ))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(
+                        format!("{:?}", resource),
+                        TextStyle {
+                            font_size: 24.,
+                            color: Color::BLACK,
+                            ..default()
+                        },
+                    ));
+                });
+        }
+    });
+}
+
+pub fn despawn_resource_chooser(mut cmd: Commands, q: Query<Entity, With<ResourceChooser>>) {
+    for entity in q.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn year_of_plenty(
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
+    mut log: ResMut<GameLog>,
+    mut progress: ResMut<ProgressCard>,
+    q_buttons: Query<(&Interaction, &ResourceChoice), Changed<Interaction>>,
+    mut next_stage: ResMut<NextState<CardStage>>,
+) {
+    for (interaction, choice) in q_buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if bank.count(choice.0) == 0 {
+            info!("the bank is out of {:?}", choice.0);
+            continue;
+        }
+        players.active_mut().add(choice.0, bank.take(choice.0, 1));
+        progress.picked.push(choice.0);
+        //an empty bank ends the card with whatever was taken so far
+        let bank_empty = hex::RESOURCES.iter().all(|&r| bank.count(r) == 0);
+        if bank_empty && progress.picked.len() < 2 {
+            info!("the bank is empty, year of plenty ends early");
+        }
+        if progress.picked.len() == 2 || bank_empty {
+            log.record(GameAction::YearOfPlenty {
+                player: players.active,
+                resources: progress.picked.clone(),
+            });
+            progress.done = true;
+            next_stage.set(CardStage::Idle);
+            return;
+        }
+    }
+}
+
+pub fn monopoly(
+    mut players: ResMut<Players>,
+    mut log: ResMut<GameLog>,
+    mut progress: ResMut<ProgressCard>,
+    q_buttons: Query<(&Interaction, &ResourceChoice), Changed<Interaction>>,
+    mut next_stage: ResMut<NextState<CardStage>>,
+) {
+    for (interaction, choice) in q_buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let active = players.active;
+        let mut taken = 0;
+        for player in players.list.iter_mut() {
+            if player.id == active {
+                continue;
+            }
+            let held = player.resources.entry(choice.0).or_default();
+            taken += *held;
+            *held = 0;
+        }
+        players.active_mut().add(choice.0, taken);
+        progress.picked.push(choice.0);
+        log.record(GameAction::Monopoly {
+            player: active,
+            resource: choice.0,
+            taken,
+        });
+        progress.done = true;
+        next_stage.set(CardStage::Idle);
+        return;
+    }
+}
@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_tweening::{lens::TransformPositionLens, Animator, EaseFunction, Tween, TweenCompleted};
+
+use super::{axial::Axial, discard::Discards, hex, RollStage};
+use crate::{
+    camera::{BoardCamera, CardCamera},
+    card::{make_resource_card, Ordinal, ResourceCard},
+    hand::{remove_from_hand, Hand},
+    loading::TextureAssets,
+    player::{PlayerId, Players},
+};
+
+//cards that don't come off the board come up from below the screen
+const OFFSCREEN_BOTTOM: f32 = -600.;
+//and spent cards go off the top, back to the bank
+const OFFSCREEN_TOP: f32 = 700.;
+
+//cards paid to a player from a hex, so they can be seen leaving it
+#[derive(Event)]
+pub struct ResourcesCollected {
+    pub player: PlayerId,
+    pub resource: hex::Resource,
+    pub amount: u32,
+    pub from: Axial,
+}
+
+//a card on its way out of the hand, gone when it gets there
+#[derive(Component)]
+pub struct SpentCard;
+
+//the hand shows the active player's resources, except while someone else is discarding
+pub fn shown_player(players: &Players, discards: &Discards, stage: &RollStage) -> PlayerId {
+    match (stage, discards.owed.first()) {
+        (RollStage::Discard, Some(&(player, _))) => player,
+        _ => players.active,
+    }
+}
+
+//keep one card in the hand for every resource the shown player holds
+pub fn sync_resource_cards(
+    mut cmd: Commands,
+    textures: Res<TextureAssets>,
+    players: Res<Players>,
+    discards: Res<Discards>,
+    stage: Res<State<RollStage>>,
+    mut shown: Local<Option<PlayerId>>,
+    mut collected: EventReader<ResourcesCollected>,
+    q_board_camera: Query<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    q_card_camera: Query<(&Camera, &GlobalTransform), With<CardCamera>>,
+    mut q_hand: Query<(Entity, &mut Hand, Option<&Children>)>,
+    q_cards: Query<&ResourceCard, Without<SpentCard>>,
+    mut q_ordinals: Query<&mut Ordinal>,
+    q_transforms: Query<&Transform>,
+) {
+    let Ok((hand_entity, mut hand, children)) = q_hand.get_single_mut() else {
+        return;
+    };
+    let owner = shown_player(&players, &discards, stage.get());
+
+    //where each new card should start, in card camera space
+    let mut origins: HashMap<hex::Resource, Vec<Vec3>> = HashMap::new();
+    for e in collected.iter() {
+        if e.player != owner {
+            continue;
+        }
+        let start = board_to_card_space(e.from.get_cartesian(), &q_board_camera, &q_card_camera);
+        for _ in 0..e.amount {
+            origins.entry(e.resource).or_default().push(start);
+        }
+    }
+    if !players.is_changed() && *shown == Some(owner) && origins.is_empty() {
+        return;
+    }
+
+    let held: Vec<(Entity, hex::Resource)> = children
+        .map(|c| {
+            c.iter()
+                .filter_map(|&c| q_cards.get(c).ok().map(|r| (c, r.0)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut removed = Vec::new();
+    let mut spent = Vec::new();
+    if *shown != Some(owner) {
+        //someone else's cards, they just disappear
+        removed.extend(held.iter().map(|&(c, _)| c));
+    }
+    let mut added = Vec::new();
+    for resource in hex::RESOURCES {
+        let have = if *shown == Some(owner) {
+            held.iter().filter(|&&(_, r)| r == resource).count() as u32
+        } else {
+            0
+        };
+        let want = *players.get(owner).resources.get(&resource).unwrap_or(&0);
+        if want > have {
+            for _ in have..want {
+                added.push(resource);
+            }
+        } else {
+            let extra = held
+                .iter()
+                .filter(|&&(_, r)| r == resource)
+                .take((have - want) as usize)
+                .map(|&(c, _)| c);
+            spent.extend(extra);
+        }
+    }
+    *shown = Some(owner);
+
+    removed.extend(spent.iter().copied());
+    if let Some(children) = children {
+        remove_from_hand(&mut hand, children, &removed, &mut q_ordinals);
+    }
+    for card in removed {
+        if spent.contains(&card) {
+            let from = q_transforms.get(card).map_or(Vec3::ZERO, |t| t.translation);
+            spend_card(&mut cmd, card, from);
+        } else {
+            cmd.entity(card).despawn_recursive();
+        }
+    }
+    for resource in added {
+        let start = origins
+            .get_mut(&resource)
+            .and_then(|o| o.pop())
+            .unwrap_or(Vec3::new(0., OFFSCREEN_BOTTOM, 0.));
+        let card = make_resource_card(&mut cmd, &textures, resource, hand.size);
+        //the hand lerps it from here into its place in the fan
+        cmd.entity(card).insert(
+            Transform::from_translation(start)
+                .with_rotation(Quat::from_rotation_y(std::f32::consts::PI)),
+        );
+        cmd.entity(hand_entity).push_children(&[card]);
+        hand.size += 1;
+    }
+}
+
+//send a card that was already taken out of the hand flying off the top of the screen
+pub fn spend_card(cmd: &mut Commands, card: Entity, from: Vec3) {
+    let tween = Tween::new(
+        EaseFunction::QuadraticIn,
+        Duration::from_millis(400),
+        TransformPositionLens {
+            start: from,
+            end: Vec3::new(from.x, OFFSCREEN_TOP, from.z),
+        },
+    )
+    .with_completed_event(0);
+    cmd.entity(card)
+        .remove_parent()
+        .insert((SpentCard, Animator::new(tween)));
+}
+
+pub fn despawn_spent_cards(
+    mut cmd: Commands,
+    mut completed: EventReader<TweenCompleted>,
+    q_spent: Query<(), With<SpentCard>>,
+) {
+    for e in completed.iter() {
+        if q_spent.contains(e.entity) {
+            cmd.entity(e.entity).despawn_recursive();
+        }
+    }
+}
+
+fn board_to_card_space(
+    pos: Vec3,
+    q_board_camera: &Query<(&Camera, &GlobalTransform), With<BoardCamera>>,
+    q_card_camera: &Query<(&Camera, &GlobalTransform), With<CardCamera>>,
+) -> Vec3 {
+    let (Ok((board_camera, board_transform)), Ok((card_camera, card_transform))) =
+        (q_board_camera.get_single(), q_card_camera.get_single())
+    else {
+        return Vec3::new(0., OFFSCREEN_BOTTOM, 0.);
+    };
+    board_camera
+        .world_to_viewport(board_transform, pos)
+        .and_then(|viewport| card_camera.viewport_to_world_2d(card_transform, viewport))
+        .map_or(Vec3::new(0., OFFSCREEN_BOTTOM, 0.), |p| p.extend(0.))
+}
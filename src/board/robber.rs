@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, render::view::RenderLayers};
+use bevy_tweening::{lens::TransformPositionLens, Animator, EaseFunction, Tween};
+
+use super::{
+    board_data::BoardData,
+    hex,
+    pickable_pos::{PickTarget, PickablePos, PickerSelected},
+    roll::Dice,
+    GameState,
+};
+use crate::{
+    loading::TextureAssets,
+    player::{PlayerId, Players},
+};
+
+#[derive(Component)]
+pub struct Robber;
+
+pub fn spawn_robber(board: Res<BoardData>, mut cmd: Commands) {
+    cmd.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.15, 0.15, 0.15),
+                custom_size: Some(Vec2::new(24., 40.)),
+                ..default()
+            },
+            transform: Transform {
+                translation: board.robber.get_cartesian() + Vec3::Z * 2.,
+                ..default()
+            },
+            ..default()
+        },
+        Robber,
+        RenderLayers::layer(1),
+    ));
+}
+
+pub fn spawn_robber_pickers(
+    board: Res<BoardData>,
+    mut cmd: Commands,
+    textures: Res<TextureAssets>,
+) {
+    for &pos in board.hexes.keys() {
+        if pos == board.robber {
+            continue;
+        }
+        cmd.spawn((
+            SpriteBundle {
+                texture: textures.pickable_pos.clone(),
+                transform: Transform {
+                    translation: pos.get_cartesian() + Vec3::Z,
+                    ..default()
+                },
+                ..default()
+            },
+            PickablePos::hex(pos),
+            RenderLayers::layer(1),
+        ));
+    }
+}
+
+pub fn move_robber(
+    mut cmd: Commands,
+    mut board: ResMut<BoardData>,
+    mut players: ResMut<Players>,
+    mut dice: ResMut<Dice>,
+    mut picked: EventReader<PickerSelected>,
+    pickers: Query<(Entity, &PickablePos)>,
+    q_robber: Query<(Entity, &Transform), With<Robber>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for p in picked.iter() {
+        let PickTarget::Hex(pos) = p.target else {
+            continue;
+        };
+        board.robber = pos;
+
+        let (entity, transform) = q_robber.single();
+        let tween = Tween::new(
+            EaseFunction::QuadraticInOut,
+            Duration::from_millis(500),
+            TransformPositionLens {
+                start: transform.translation,
+                end: pos.get_cartesian() + Vec3::Z * 2.,
+            },
+        );
+        cmd.entity(entity).insert(Animator::new(tween));
+
+        let thief = players.active;
+        let victims: Vec<PlayerId> = board
+            .owners_on_hex(pos)
+            .into_iter()
+            .filter(|&o| o != thief && players.get(o).card_count() > 0)
+            .collect();
+        if !victims.is_empty() {
+            let victim = victims[dice.pick(victims.len())];
+            if let Some(resource) = steal(&mut players, &mut dice, victim, thief) {
+                info!(
+                    "{} stole {:?} from {}",
+                    players.get(thief).name,
+                    resource,
+                    players.get(victim).name
+                );
+            }
+        }
+
+        for (entity, picker) in pickers.iter() {
+            if let PickTarget::Hex(_) = picker.target() {
+                cmd.entity(entity).despawn_recursive();
+            }
+        }
+        next_state.set(GameState::Turn);
+        return;
+    }
+}
+
+//take one card at random from the victim, every card is equally likely
+fn steal(
+    players: &mut Players,
+    dice: &mut Dice,
+    victim: PlayerId,
+    thief: PlayerId,
+) -> Option<hex::Resource> {
+    let total = players.get(victim).card_count();
+    if total == 0 {
+        return None;
+    }
+    let mut index = dice.pick(total as usize) as u32;
+    let mut stolen = None;
+    for (&resource, count) in players.get_mut(victim).resources.iter_mut() {
+        if index < *count {
+            *count -= 1;
+            stolen = Some(resource);
+            break;
+        }
+        index -= *count;
+    }
+    let resource = stolen?;
+    players.get_mut(thief).add(resource, 1);
+    Some(resource)
+}
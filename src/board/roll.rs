@@ -0,0 +1,144 @@
+use bevy::prelude::*;
+use leafwing_input_manager::{
+    prelude::{ActionState, InputMap},
+    Actionlike, InputManagerBundle,
+};
+use random::Source;
+
+use super::{
+    bank::Bank, board_data::BoardData, resource_cards::ResourcesCollected, GameState, RollStage,
+};
+use crate::player::{PlayerId, Players};
+
+pub const DICE_SEED: u64 = 0x5eed;
+
+#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+pub enum TurnAction {
+    Roll,
+    EndTurn,
+    BuildSettlement,
+    BuildRoad,
+    UpgradeCity,
+    Cancel,
+    Trade,
+}
+
+#[derive(Resource)]
+pub struct Dice {
+    source: random::Default,
+}
+impl Dice {
+    pub fn new(seed: u64) -> Self {
+        Dice {
+            source: random::default(seed),
+        }
+    }
+    pub fn roll(&mut self) -> (i32, i32) {
+        (self.d6(), self.d6())
+    }
+    //a new seed drawn from the dice, used to lay out the next board
+    pub fn seed(&mut self) -> u64 {
+        self.source.read_u64()
+    }
+    //pick an index below n from the same seeded source as the dice
+    pub fn pick(&mut self, n: usize) -> usize {
+        (self.source.read_u64() % n as u64) as usize
+    }
+    fn d6(&mut self) -> i32 {
+        (self.source.read_u64() % 6) as i32 + 1
+    }
+}
+
+#[derive(Event)]
+pub struct DiceRolled {
+    pub total: i32,
+}
+#[derive(Event)]
+pub struct TurnEnded {
+    pub player: PlayerId,
+}
+
+pub fn spawn_turn_input(mut cmd: Commands) {
+    cmd.spawn(InputManagerBundle::<TurnAction> {
+        action_state: ActionState::default(),
+        input_map: InputMap::new([
+            (KeyCode::R, TurnAction::Roll),
+            (KeyCode::Return, TurnAction::EndTurn),
+            (KeyCode::S, TurnAction::BuildSettlement),
+            (KeyCode::B, TurnAction::BuildRoad),
+            (KeyCode::C, TurnAction::UpgradeCity),
+            (KeyCode::Escape, TurnAction::Cancel),
+            (KeyCode::T, TurnAction::Trade),
+        ]),
+    });
+}
+
+pub fn roll_dice(
+    q: Query<&ActionState<TurnAction>>,
+    mut dice: ResMut<Dice>,
+    players: Res<Players>,
+    mut roll_writer: EventWriter<DiceRolled>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_stage: ResMut<NextState<RollStage>>,
+) {
+    let action_state = q.single();
+
+    if action_state.just_pressed(TurnAction::Roll) {
+        let (a, b) = dice.roll();
+        info!("rolled {} + {} = {}", a, b, a + b);
+        roll_writer.send(DiceRolled { total: a + b });
+        if a + b == 7 && players.list.iter().any(|p| p.card_count() > 7) {
+            next_stage.set(RollStage::Discard);
+        } else if a + b == 7 {
+            next_state.set(GameState::Robber);
+        } else {
+            next_state.set(GameState::Turn);
+        }
+    }
+}
+
+pub fn produce_resources(
+    board: Res<BoardData>,
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
+    mut roll_reader: EventReader<DiceRolled>,
+    mut collected_writer: EventWriter<ResourcesCollected>,
+) {
+    for roll in roll_reader.iter() {
+        for (owner, resource, amount) in bank.pay_out(board.production(roll.total)) {
+            players.get_mut(owner).add(resource, amount);
+            //show the cards leaving one of the hexes that paid them
+            let from = board.hexes.values().find(|h| {
+                h.number == roll.total
+                    && h.resource_type == resource
+                    && h.pos != board.robber
+                    && board.owners_on_hex(h.pos).contains(&owner)
+            });
+            if let Some(h) = from {
+                collected_writer.send(ResourcesCollected {
+                    player: owner,
+                    resource,
+                    amount,
+                    from: h.pos,
+                });
+            }
+        }
+    }
+}
+
+pub fn end_turn(
+    q: Query<&ActionState<TurnAction>>,
+    mut players: ResMut<Players>,
+    mut ended_writer: EventWriter<TurnEnded>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let action_state = q.single();
+
+    if action_state.just_pressed(TurnAction::EndTurn) {
+        ended_writer.send(TurnEnded {
+            player: players.active,
+        });
+        players.next();
+        next_state.set(GameState::Roll);
+    }
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+use super::{
+    bank::Bank, board_data::BoardData, hex, resource_cards::ResourcesCollected, spawn_edge_pickers,
+    spawn_settlement_pickers, GameState, RoadPlaced, SettlementPlaced,
+};
+use crate::{
+    loading::TextureAssets,
+    player::{PlayerId, Players},
+};
+
+//each step is one settlement and the road next to it, players go 1..N then N..1
+#[derive(Resource, Default)]
+pub struct Setup {
+    pub step: usize,
+}
+impl Setup {
+    pub fn player(&self, players: usize) -> Option<PlayerId> {
+        if self.step < players {
+            Some(PlayerId(self.step))
+        } else if self.step < players * 2 {
+            Some(PlayerId(players * 2 - 1 - self.step))
+        } else {
+            None
+        }
+    }
+    pub fn is_second_round(&self, players: usize) -> bool {
+        self.step >= players
+    }
+}
+
+pub fn advance_setup(
+    mut cmd: Commands,
+    board: Res<BoardData>,
+    textures: Res<TextureAssets>,
+    mut bank: ResMut<Bank>,
+    mut setup: ResMut<Setup>,
+    mut players: ResMut<Players>,
+    mut settlements: EventReader<SettlementPlaced>,
+    mut roads: EventReader<RoadPlaced>,
+    mut collected_writer: EventWriter<ResourcesCollected>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for s in settlements.iter() {
+        if setup.is_second_round(players.count()) {
+            for h in board.get_adjacent_hexes(s.pos) {
+                if h.resource_type == hex::Resource::None {
+                    continue;
+                }
+                let amount = bank.take(h.resource_type, 1);
+                players.get_mut(s.owner).add(h.resource_type, amount);
+                collected_writer.send(ResourcesCollected {
+                    player: s.owner,
+                    resource: h.resource_type,
+                    amount,
+                    from: h.pos,
+                });
+            }
+        }
+        let edges = board
+            .get_incident_edges(s.pos)
+            .into_iter()
+            .filter(|e| e.owner.is_none())
+            .collect();
+        spawn_edge_pickers(&mut cmd, &textures, edges);
+    }
+
+    for _ in roads.iter() {
+        setup.step += 1;
+        match setup.player(players.count()) {
+            Some(player) => {
+                players.active = player;
+                spawn_settlement_pickers(&mut cmd, &textures, &board);
+            },
+            None => {
+                players.active = PlayerId(0);
+                next_state.set(GameState::Roll);
+            },
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use bevy::{
+    prelude::*,
+    render::view::RenderLayers,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+use super::{
+    axial::Axial,
+    board_data::BoardData,
+    generate::pips,
+    harbor::{Harbor, HarborKind},
+    hex::{self, resource_tint},
+};
+use crate::loading::TextureAssets;
+
+//remembers what a tile was drawn from so it can be redrawn when the board changes under it
+#[derive(Component)]
+pub struct Tile {
+    pos: Axial,
+    resource: hex::Resource,
+    number: i32,
+}
+#[derive(Component)]
+pub struct HarborSprite;
+
+pub fn sync_board(
+    board: Res<BoardData>,
+    mut cmd: Commands,
+    textures: Res<TextureAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    q_tiles: Query<(Entity, &Tile)>,
+    q_harbors: Query<Entity, With<HarborSprite>>,
+) {
+    if !board.is_changed() {
+        return;
+    }
+    let stale = q_tiles.iter().count() != board.hexes.len()
+        || q_tiles.iter().any(|(_, t)| {
+            board
+                .hexes
+                .get(&t.pos)
+                .is_none_or(|h| h.resource_type != t.resource || h.number != t.number)
+        });
+    if !stale {
+        return;
+    }
+    for entity in q_tiles.iter().map(|(e, _)| e).chain(q_harbors.iter()) {
+        cmd.entity(entity).despawn_recursive();
+    }
+    spawn_board(&board, &mut cmd, &textures, &mut meshes, &mut materials);
+}
+
+pub fn spawn_board(
+    board: &BoardData,
+    cmd: &mut Commands,
+    textures: &TextureAssets,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+) {
+    for pos in board.hexes.keys() {
+        let h = board.hexes[pos];
+        let t = tile_texture(textures, h.resource_type);
+        let tile = cmd
+            .spawn((
+                SpriteBundle {
+                    texture: t,
+                    transform: Transform {
+                        translation: pos.get_cartesian(),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Tile {
+                    pos: *pos,
+                    resource: h.resource_type,
+                    number: h.number,
+                },
+                RenderLayers::layer(1),
+            ))
+            .id();
+        if h.resource_type != hex::Resource::None {
+            let token = spawn_number_token(cmd, meshes, materials, h.number);
+            cmd.entity(tile).push_children(&[token]);
+        }
+    }
+    for harbor in board.harbors.iter() {
+        spawn_harbor(cmd, meshes, materials, harbor);
+    }
+}
+
+pub fn tile_texture(textures: &TextureAssets, resource: hex::Resource) -> Handle<Image> {
+    match resource {
+        hex::Resource::None => textures.desert_tile.clone(),
+        hex::Resource::Ore => textures.ore_tile.clone(),
+        hex::Resource::Wheat => textures.wheat_tile.clone(),
+        hex::Resource::Sheep => textures.sheep_tile.clone(),
+        hex::Resource::Brick => textures.brick_tile.clone(),
+        hex::Resource::Wood => textures.wood_tile.clone(),
+    }
+}
+
+//a token out in the sea with the trade ratio, and a pier to each corner that can use it
+fn spawn_harbor(
+    cmd: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    harbor: &Harbor,
+) {
+    let (a, b) = harbor.coords.ends();
+    let (a, b) = (a.get_cartesian(), b.get_cartesian());
+    let mid = (a + b) / 2.;
+    let outward = (mid - harbor.hex.get_cartesian()).normalize_or_zero();
+    let pos = mid + outward * 36.;
+    let color = match harbor.kind {
+        HarborKind::Generic => Color::rgb(0.96, 0.9, 0.75),
+        HarborKind::Special(resource) => resource_tint(resource),
+    };
+
+    for end in [a, b] {
+        let d = end - pos;
+        cmd.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgb(0.45, 0.3, 0.15),
+                    custom_size: Some(Vec2::new(d.length(), 4.)),
+                    ..default()
+                },
+                transform: Transform {
+                    translation: (pos + end) / 2. + Vec3::Z * 0.05,
+                    rotation: Quat::from_rotation_z(d.y.atan2(d.x)),
+                    ..default()
+                },
+                ..default()
+            },
+            HarborSprite,
+            RenderLayers::layer(1),
+        ));
+    }
+    let token = cmd
+        .spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(shape::Circle::new(16.).into())),
+                material: materials.add(ColorMaterial::from(color)),
+                transform: Transform::from_translation(pos + Vec3::Z * 0.1),
+                ..default()
+            },
+            HarborSprite,
+            RenderLayers::layer(1),
+        ))
+        .id();
+    let text = cmd
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("{}:1", harbor.kind.ratio()),
+                    TextStyle {
+                        font_size: 16.,
+                        color: Color::rgb(0.1, 0.1, 0.1),
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_xyz(0., 0., 0.1),
+                ..default()
+            },
+            RenderLayers::layer(1),
+        ))
+        .id();
+    cmd.entity(token).push_children(&[text]);
+}
+
+//a round token with the number and one dot per way of rolling it, 6 and 8 are red
+fn spawn_number_token(
+    cmd: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    number: i32,
+) -> Entity {
+    let color = if number == 6 || number == 8 {
+        Color::rgb(0.8, 0.1, 0.1)
+    } else {
+        Color::rgb(0.1, 0.1, 0.1)
+    };
+    let token = cmd
+        .spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(shape::Circle::new(18.).into())),
+                material: materials.add(ColorMaterial::from(Color::rgb(0.96, 0.9, 0.75))),
+                transform: Transform::from_xyz(0., 0., 0.1),
+                ..default()
+            },
+            RenderLayers::layer(1),
+        ))
+        .id();
+    let text = cmd
+        .spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    number.to_string(),
+                    TextStyle {
+                        font_size: 20.,
+                        color,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_xyz(0., 3., 0.1),
+                ..default()
+            },
+            RenderLayers::layer(1),
+        ))
+        .id();
+    cmd.entity(token).push_children(&[text]);
+
+    let dots = pips(number);
+    let dot_mesh = Mesh2dHandle(meshes.add(shape::Circle::new(1.5).into()));
+    let dot_material = materials.add(ColorMaterial::from(color));
+    for i in 0..dots {
+        let x = (i as f32 - (dots - 1) as f32 / 2.) * 4.;
+        let dot = cmd
+            .spawn((
+                MaterialMesh2dBundle {
+                    mesh: dot_mesh.clone(),
+                    material: dot_material.clone(),
+                    transform: Transform::from_xyz(x, -10., 0.1),
+                    ..default()
+                },
+                RenderLayers::layer(1),
+            ))
+            .id();
+        cmd.entity(token).push_children(&[dot]);
+    }
+    token
+}
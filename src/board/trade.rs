@@ -0,0 +1,412 @@
+use bevy::prelude::*;
+
+use super::{
+    hex,
+    log::{GameAction, GameLog},
+    roll::TurnEnded,
+};
+use crate::player::{PlayerId, Players};
+
+//an offer between two players, from gives what's in give and wants what's in get
+#[derive(Debug, Clone)]
+pub struct TradeOffer {
+    pub id: u32,
+    pub from: PlayerId,
+    //None is open to every opponent
+    pub to: Option<PlayerId>,
+    pub give: Vec<(hex::Resource, u32)>,
+    pub get: Vec<(hex::Resource, u32)>,
+    //the offer this one answers
+    pub counter_of: Option<u32>,
+}
+
+#[derive(Resource, Default)]
+pub struct TradeOffers {
+    next_id: u32,
+    pub open: Vec<TradeOffer>,
+    //(offer, player) pairs that are happy to go ahead
+    pub accepted: Vec<(u32, PlayerId)>,
+}
+impl TradeOffers {
+    pub fn get(&self, id: u32) -> Option<&TradeOffer> {
+        self.open.iter().find(|o| o.id == id)
+    }
+    fn push(&mut self, mut offer: TradeOffer) -> u32 {
+        offer.id = self.next_id;
+        self.next_id += 1;
+        let id = offer.id;
+        self.open.push(offer);
+        id
+    }
+    fn remove(&mut self, id: u32) {
+        self.open.retain(|o| o.id != id);
+        self.accepted.retain(|&(o, _)| o != id);
+    }
+}
+
+//the active player puts an offer on the table
+#[derive(Event)]
+pub struct ProposeTrade {
+    pub to: Option<PlayerId>,
+    pub give: Vec<(hex::Resource, u32)>,
+    pub get: Vec<(hex::Resource, u32)>,
+}
+//an opponent answers an offer with different terms, from their side
+#[derive(Event)]
+pub struct CounterTrade {
+    pub offer: u32,
+    pub from: PlayerId,
+    pub give: Vec<(hex::Resource, u32)>,
+    pub get: Vec<(hex::Resource, u32)>,
+}
+#[derive(Event)]
+pub struct AcceptTrade {
+    pub offer: u32,
+    pub by: PlayerId,
+}
+#[derive(Event)]
+pub struct RejectTrade {
+    pub offer: u32,
+    pub by: PlayerId,
+}
+//the active player goes through with an offer, with whoever accepted it or made the counter
+#[derive(Event)]
+pub struct CompleteTrade {
+    pub offer: u32,
+    pub with: PlayerId,
+}
+
+//both sides have to put something real in, and nobody swaps a resource for itself
+fn fair_bundles(give: &[(hex::Resource, u32)], get: &[(hex::Resource, u32)]) -> bool {
+    let real = |bundle: &[(hex::Resource, u32)]| {
+        !bundle.is_empty()
+            && bundle
+                .iter()
+                .all(|&(resource, amount)| resource != hex::Resource::None && amount > 0)
+    };
+    real(give) && real(get) && !give.iter().any(|g| get.iter().any(|w| w.0 == g.0))
+}
+
+//events name players directly, one that isn't in the game is dropped
+fn in_game(players: &Players, id: PlayerId) -> bool {
+    players.ids().any(|p| p == id)
+}
+
+pub fn propose_trades(
+    players: Res<Players>,
+    mut offers: ResMut<TradeOffers>,
+    mut proposed: EventReader<ProposeTrade>,
+    mut countered: EventReader<CounterTrade>,
+) {
+    for e in proposed.iter() {
+        if e.to == Some(players.active) || e.to.is_some_and(|to| !in_game(&players, to)) {
+            continue;
+        }
+        if !fair_bundles(&e.give, &e.get) {
+            info!("{} can't offer that trade", players.active().name);
+            continue;
+        }
+        let id = offers.push(TradeOffer {
+            id: 0,
+            from: players.active,
+            to: e.to,
+            give: e.give.clone(),
+            get: e.get.clone(),
+            counter_of: None,
+        });
+        info!("{} offers trade {}", players.active().name, id);
+    }
+    for e in countered.iter() {
+        //only the active player trades, so counters have to answer their offers
+        let answers_active = offers
+            .get(e.offer)
+            .is_some_and(|o| o.from == players.active && o.to.is_none_or(|to| to == e.from));
+        if !answers_active || e.from == players.active || !in_game(&players, e.from) {
+            continue;
+        }
+        if !fair_bundles(&e.give, &e.get) {
+            info!("{} can't counter with that", players.get(e.from).name);
+            continue;
+        }
+        let id = offers.push(TradeOffer {
+            id: 0,
+            from: e.from,
+            to: Some(players.active),
+            give: e.give.clone(),
+            get: e.get.clone(),
+            counter_of: Some(e.offer),
+        });
+        info!(
+            "{} counters trade {} with {}",
+            players.get(e.from).name,
+            e.offer,
+            id
+        );
+    }
+}
+
+pub fn answer_trades(
+    players: Res<Players>,
+    mut offers: ResMut<TradeOffers>,
+    mut accepted: EventReader<AcceptTrade>,
+    mut rejected: EventReader<RejectTrade>,
+) {
+    for e in accepted.iter() {
+        if !in_game(&players, e.by) {
+            continue;
+        }
+        let Some(offer) = offers.get(e.offer) else {
+            continue;
+        };
+        if offer.from == e.by || offer.to.is_some_and(|to| to != e.by) {
+            continue;
+        }
+        if !offers.accepted.contains(&(e.offer, e.by)) {
+            offers.accepted.push((e.offer, e.by));
+        }
+    }
+    for e in rejected.iter() {
+        if !in_game(&players, e.by) {
+            continue;
+        }
+        offers.accepted.retain(|&a| a != (e.offer, e.by));
+        //an offer made to one player is gone once they turn it down, and either side of a
+        //counter can drop it
+        let gone = offers
+            .get(e.offer)
+            .is_some_and(|o| o.to == Some(e.by) || (o.counter_of.is_some() && o.from == e.by));
+        if gone {
+            offers.remove(e.offer);
+        }
+    }
+}
+
+//holdings are only checked here since they can change while an offer sits on the table
+pub fn complete_trades(
+    mut players: ResMut<Players>,
+    mut offers: ResMut<TradeOffers>,
+    mut log: ResMut<GameLog>,
+    mut completed: EventReader<CompleteTrade>,
+) {
+    for e in completed.iter() {
+        if !in_game(&players, e.with) {
+            continue;
+        }
+        let Some(offer) = offers.get(e.offer).cloned() else {
+            continue;
+        };
+        let agreed = if offer.from == players.active {
+            offers.accepted.contains(&(offer.id, e.with))
+        } else {
+            //confirming a counter is the active player agreeing to it
+            offer.from == e.with && offer.to == Some(players.active)
+        };
+        if !agreed {
+            info!(
+                "nobody has agreed to trade {} with {}",
+                offer.id,
+                players.get(e.with).name
+            );
+            continue;
+        }
+        let giver = offer.from;
+        let taker = if giver == e.with {
+            players.active
+        } else {
+            e.with
+        };
+        if !players.get(giver).has_all(&offer.give) || !players.get(taker).has_all(&offer.get) {
+            info!(
+                "trade {} can't go ahead, someone is missing cards",
+                offer.id
+            );
+            offers.remove(offer.id);
+            continue;
+        }
+
+        players.get_mut(giver).take_all(&offer.give);
+        players.get_mut(taker).take_all(&offer.get);
+        for &(resource, amount) in offer.give.iter() {
+            players.get_mut(taker).add(resource, amount);
+        }
+        for &(resource, amount) in offer.get.iter() {
+            players.get_mut(giver).add(resource, amount);
+        }
+        log.record(GameAction::Trade {
+            from: giver,
+            to: taker,
+            give: offer.give.clone(),
+            get: offer.get.clone(),
+        });
+
+        //the offer and anything answering it are done with
+        let answers: Vec<u32> = offers
+            .open
+            .iter()
+            .filter(|o| o.counter_of == Some(offer.id) || offer.counter_of == Some(o.id))
+            .map(|o| o.id)
+            .collect();
+        offers.remove(offer.id);
+        for id in answers {
+            offers.remove(id);
+        }
+    }
+}
+
+//offers don't carry over to the next player's turn, they do wait out the robber
+pub fn clear_trades(mut offers: ResMut<TradeOffers>, mut ended: EventReader<TurnEnded>) {
+    if ended.iter().count() > 0 {
+        offers.open.clear();
+        offers.accepted.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::hex::Resource::{Brick, Wood};
+
+    const P0: PlayerId = PlayerId(0);
+    const P1: PlayerId = PlayerId(1);
+
+    fn world() -> (World, Schedule) {
+        let mut world = World::new();
+        let mut players = Players::new(3);
+        players.get_mut(P0).add(Wood, 2);
+        players.get_mut(P1).add(Brick, 2);
+        world.insert_resource(players);
+        world.init_resource::<TradeOffers>();
+        world.init_resource::<GameLog>();
+        world.init_resource::<Events<ProposeTrade>>();
+        world.init_resource::<Events<CounterTrade>>();
+        world.init_resource::<Events<AcceptTrade>>();
+        world.init_resource::<Events<RejectTrade>>();
+        world.init_resource::<Events<CompleteTrade>>();
+        let mut schedule = Schedule::new();
+        schedule.add_systems((propose_trades, answer_trades, complete_trades).chain());
+        (world, schedule)
+    }
+
+    fn propose(world: &mut World, schedule: &mut Schedule, to: Option<PlayerId>) {
+        world.send_event(ProposeTrade {
+            to,
+            give: vec![(Wood, 1)],
+            get: vec![(Brick, 1)],
+        });
+        schedule.run(world);
+    }
+
+    fn held(world: &World, id: PlayerId, resource: hex::Resource) -> u32 {
+        *world
+            .resource::<Players>()
+            .get(id)
+            .resources
+            .get(&resource)
+            .unwrap_or(&0)
+    }
+
+    #[test]
+    fn accepted_offer_goes_through() {
+        let (mut world, mut schedule) = world();
+        propose(&mut world, &mut schedule, None);
+        let offer = world.resource::<TradeOffers>().open[0].id;
+
+        //nothing happens until someone accepts
+        world.send_event(CompleteTrade { offer, with: P1 });
+        schedule.run(&mut world);
+        assert_eq!(held(&world, P0, Wood), 2);
+
+        world.send_event(AcceptTrade { offer, by: P1 });
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<TradeOffers>().accepted, vec![(offer, P1)]);
+
+        world.send_event(CompleteTrade { offer, with: P1 });
+        schedule.run(&mut world);
+        assert_eq!(held(&world, P0, Wood), 1);
+        assert_eq!(held(&world, P0, Brick), 1);
+        assert_eq!(held(&world, P1, Wood), 1);
+        assert_eq!(held(&world, P1, Brick), 1);
+        assert!(world.resource::<TradeOffers>().open.is_empty());
+        assert_eq!(world.resource::<GameLog>().actions.len(), 1);
+    }
+
+    #[test]
+    fn confirmed_counter_goes_through() {
+        let (mut world, mut schedule) = world();
+        propose(&mut world, &mut schedule, Some(P1));
+        let offer = world.resource::<TradeOffers>().open[0].id;
+
+        world.send_event(CounterTrade {
+            offer,
+            from: P1,
+            give: vec![(Brick, 1)],
+            get: vec![(Wood, 2)],
+        });
+        schedule.run(&mut world);
+        let counter = world.resource::<TradeOffers>().open[1].clone();
+        assert_eq!(counter.from, P1);
+        assert_eq!(counter.to, Some(P0));
+        assert_eq!(counter.counter_of, Some(offer));
+
+        world.send_event(CompleteTrade {
+            offer: counter.id,
+            with: P1,
+        });
+        schedule.run(&mut world);
+        assert_eq!(held(&world, P0, Wood), 0);
+        assert_eq!(held(&world, P0, Brick), 1);
+        assert_eq!(held(&world, P1, Wood), 2);
+        assert_eq!(held(&world, P1, Brick), 1);
+        //the offer the counter answered is gone too
+        assert!(world.resource::<TradeOffers>().open.is_empty());
+    }
+
+    #[test]
+    fn holdings_are_checked_when_confirming() {
+        let (mut world, mut schedule) = world();
+        propose(&mut world, &mut schedule, None);
+        let offer = world.resource::<TradeOffers>().open[0].id;
+        world.send_event(AcceptTrade { offer, by: P1 });
+        schedule.run(&mut world);
+
+        //the brick is spent while the offer sits on the table
+        world
+            .resource_mut::<Players>()
+            .get_mut(P1)
+            .take_all(&[(Brick, 2)]);
+        world.send_event(CompleteTrade { offer, with: P1 });
+        schedule.run(&mut world);
+        assert_eq!(held(&world, P0, Wood), 2);
+        assert_eq!(held(&world, P1, Wood), 0);
+        assert!(world.resource::<TradeOffers>().open.is_empty());
+        assert!(world.resource::<GameLog>().actions.is_empty());
+    }
+
+    #[test]
+    fn unknown_players_are_ignored() {
+        let (mut world, mut schedule) = world();
+        propose(&mut world, &mut schedule, Some(PlayerId(99)));
+        assert!(world.resource::<TradeOffers>().open.is_empty());
+
+        propose(&mut world, &mut schedule, None);
+        let offer = world.resource::<TradeOffers>().open[0].id;
+        world.send_event(CounterTrade {
+            offer,
+            from: PlayerId(99),
+            give: vec![(Brick, 1)],
+            get: vec![(Wood, 1)],
+        });
+        world.send_event(AcceptTrade {
+            offer,
+            by: PlayerId(99),
+        });
+        world.send_event(CompleteTrade {
+            offer,
+            with: PlayerId(99),
+        });
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<TradeOffers>().open.len(), 1);
+        assert!(world.resource::<TradeOffers>().accepted.is_empty());
+        assert_eq!(held(&world, P0, Wood), 2);
+    }
+}
@@ -1,13 +1,20 @@
-use super::{axial::Axial, hex::BuildType};
+use bevy::prelude::Vec3;
+
+use super::{axial::Axial, edge::EdgeCoord, hex::BuildType};
+use crate::player::PlayerId;
+
+//lattice steps from a hex center to its top and bottom corners
+const NORTH: Axial = Axial::new(1, 0);
+const SOUTH: Axial = Axial::new(-1, 0);
 
 #[derive(Debug, Clone, Copy)]
 pub struct Vertex {
-    pub pos: Axial,
+    pub pos: VertexCoord,
     pub build_type: BuildType,
-    pub owner: i32,
+    pub owner: Option<PlayerId>,
 }
 impl Vertex {
-    pub fn new(pos: Axial, build_type: BuildType, owner: i32) -> Self {
+    pub fn new(pos: VertexCoord, build_type: BuildType, owner: Option<PlayerId>) -> Self {
         Vertex {
             pos,
             build_type,
@@ -15,3 +22,78 @@ impl Vertex {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Corner {
+    N,
+    S,
+}
+
+/// A corner of the board, every corner is the top of exactly one hex or the bottom of exactly
+/// one, so there's only one way to write it down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VertexCoord {
+    pub hex: Axial,
+    pub corner: Corner,
+}
+impl VertexCoord {
+    pub fn new(hex: Axial, corner: Corner) -> Self {
+        VertexCoord { hex, corner }
+    }
+
+    //the corner at a point of the lattice hexes and corners share, hex centers aren't corners
+    pub fn from_lattice(a: Axial) -> Option<Self> {
+        if a.is_hex_center() {
+            None
+        } else if (a - NORTH).is_hex_center() {
+            Some(VertexCoord::new(a - NORTH, Corner::N))
+        } else {
+            Some(VertexCoord::new(a - SOUTH, Corner::S))
+        }
+    }
+    pub fn lattice(&self) -> Axial {
+        match self.corner {
+            Corner::N => self.hex + NORTH,
+            Corner::S => self.hex + SOUTH,
+        }
+    }
+    pub fn get_cartesian(&self) -> Vec3 {
+        self.lattice().get_cartesian()
+    }
+
+    /// The six corners of a hex, clockwise from the top
+    pub fn corners_of(hex: Axial) -> [VertexCoord; 6] {
+        Axial::DIRECTIONS.map(|d| {
+            VertexCoord::from_lattice(hex + d).expect("a step off a hex center is a corner")
+        })
+    }
+
+    /// The three hexes meeting at the corner, whether they're on the board or not
+    pub fn hexes(&self) -> [Axial; 3] {
+        let h = self.hex;
+        match self.corner {
+            Corner::N => [h, h + Axial::new(1, 1), h + Axial::new(2, -1)],
+            Corner::S => [h, h + Axial::new(-1, -1), h + Axial::new(-2, 1)],
+        }
+    }
+
+    /// The three corners one road away
+    pub fn adjacent_vertices(&self) -> [VertexCoord; 3] {
+        let mut corners = self
+            .lattice()
+            .neighbors()
+            .into_iter()
+            .filter_map(VertexCoord::from_lattice);
+        [(); 3].map(|_| {
+            corners
+                .next()
+                .expect("every other lattice step is a corner")
+        })
+    }
+
+    /// The three roads that could leave the corner
+    pub fn edges(&self) -> [EdgeCoord; 3] {
+        self.adjacent_vertices()
+            .map(|v| EdgeCoord::between(*self, v).expect("adjacent corners share an edge"))
+    }
+}
@@ -50,6 +50,8 @@ fn setup(mut cmd: Commands) {
         },
         BoardCamera,
         RenderLayers::layer(1),
+        // ui is drawn once, by the card camera on top
+        UiCameraConfig { show_ui: false },
     ))
     .insert(PanCam::default());
 }
@@ -1,7 +1,13 @@
+use std::f32::consts::PI;
+
 use bevy::prelude::*;
 use leafwing_input_manager::{prelude::InputManagerPlugin, Actionlike};
 
-use crate::{loading::TextureAssets, AppState};
+use crate::{
+    board::{hex, tile::tile_texture},
+    loading::TextureAssets,
+    AppState,
+};
 
 #[derive(Component)]
 pub struct Card {
@@ -31,6 +37,10 @@ pub struct CardFace {
 #[derive(Component)]
 pub struct Ordinal(pub usize);
 
+//a card standing in for one resource a player holds
+#[derive(Component)]
+pub struct ResourceCard(pub hex::Resource);
+
 #[derive(Component)]
 pub struct Flipping {
     half: bool,
@@ -47,6 +57,118 @@ impl Plugin for CardPlugin {
             .add_event::<FlipCard>();
     }
 }
+pub fn make_card(
+    cmd: &mut Commands,
+    front_texture: Handle<Image>,
+    back_texture: Handle<Image>,
+    tint: Color,
+    ordinal: usize,
+    face_up: bool,
+) -> Entity {
+    make_card_with_front(cmd, front_texture, back_texture, tint, ordinal, face_up).0
+}
+//a resource card is a blank card with the resource's tile drawn on its front
+pub fn make_resource_card(
+    cmd: &mut Commands,
+    textures: &TextureAssets,
+    resource: hex::Resource,
+    ordinal: usize,
+) -> Entity {
+    let (card, front) = make_card_with_front(
+        cmd,
+        textures.card_blank.clone(),
+        textures.card_red.clone(),
+        Color::WHITE,
+        ordinal,
+        true,
+    );
+    let picture = cmd
+        .spawn(SpriteBundle {
+            texture: tile_texture(textures, resource),
+            transform: Transform {
+                translation: Vec3::Z * 0.1,
+                scale: Vec3::splat(0.8),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    cmd.entity(front).push_children(&[picture]);
+    cmd.entity(card).insert(ResourceCard(resource));
+    card
+}
+//returns the card and its front face, so things can be drawn on top of the face
+pub fn make_card_with_front(
+    cmd: &mut Commands,
+    front_texture: Handle<Image>,
+    back_texture: Handle<Image>,
+    tint: Color,
+    ordinal: usize,
+    face_up: bool,
+) -> (Entity, Entity) {
+    let front = cmd
+        .spawn((
+            SpriteBundle {
+                texture: front_texture,
+                sprite: Sprite {
+                    color: tint,
+                    ..default()
+                },
+                visibility: if face_up {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                },
+                transform: Transform {
+                    rotation: Quat::from_euler(EulerRot::XYZ, 0., PI, 0.),
+                    ..default()
+                },
+
+                ..default()
+            },
+            CardFace { is_front: true },
+        ))
+        .id();
+    let back = cmd
+        .spawn((
+            SpriteBundle {
+                texture: back_texture,
+                visibility: if face_up {
+                    Visibility::Hidden
+                } else {
+                    Visibility::Visible
+                },
+                ..default()
+            },
+            CardFace { is_front: false },
+        ))
+        .id();
+
+    let card_id = cmd
+        .spawn(CardBundle {
+            card: Card {
+                back,
+                front,
+                face_up,
+            },
+            sprite: SpriteBundle {
+                transform: Transform {
+                    rotation: if face_up {
+                        Quat::from_rotation_y(PI)
+                    } else {
+                        Quat::IDENTITY
+                    },
+                    ..default()
+                },
+                ..default()
+            },
+            ordinal: Ordinal(ordinal),
+        })
+        .id();
+
+    cmd.entity(card_id).push_children(&[front, back]);
+    (card_id, front)
+}
 //TODO rotate in axis of rotation so the card flips not in y unless straight
 pub fn flip_card(
     mut q_cards: Query<(Entity, &mut Card), Without<Flipping>>,
@@ -1,5 +1,3 @@
-use std::f32::consts::PI;
-
 use bevy::prelude::*;
 use leafwing_input_manager::{
     prelude::{ActionState, InputManagerPlugin, InputMap},
@@ -7,12 +5,50 @@ use leafwing_input_manager::{
 };
 
 use crate::{
-    card::{Card, CardBundle, CardFace, FlipCard, Ordinal},
-    hand::Hand,
+    board::{
+        bank::Bank, cost::Build, discard::Discards, generate::shuffle,
+        resource_cards::shown_player, roll::Dice, GameState, RollStage,
+    },
+    card::{make_card, Card, Ordinal},
+    hand::{remove_from_hand, Hand},
     loading::TextureAssets,
+    player::{BonusCard, PlayerId, Players},
     AppState,
 };
 
+const DEV_CARDS: [(DevCard, usize); 5] = [
+    (DevCard::Knight, 14),
+    (DevCard::VictoryPoint, 5),
+    (DevCard::RoadBuilding, 2),
+    (DevCard::YearOfPlenty, 2),
+    (DevCard::Monopoly, 2),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DevCard {
+    Knight,
+    VictoryPoint,
+    RoadBuilding,
+    YearOfPlenty,
+    Monopoly,
+}
+
+//a development card still in the deck, gets an owner once it is bought
+#[derive(Component)]
+pub struct DevelopmentCard {
+    pub kind: DevCard,
+    pub bought: Option<(PlayerId, u32)>,
+}
+impl DevelopmentCard {
+    //cards can't be played on the turn they were bought
+    pub fn playable(&self, players: &Players) -> bool {
+        match self.bought {
+            Some((owner, turn)) => owner == players.active && players.turn > turn,
+            None => false,
+        }
+    }
+}
+
 // This is the list of "things in the game I want to be able to do based on input"
 #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
 pub enum DeckAction {
@@ -29,23 +65,33 @@ pub struct DeckPlugin;
 impl Plugin for DeckPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Playing), spawn_deck)
+            .add_systems(OnExit(AppState::Playing), despawn_deck)
             .add_systems(
                 Update,
-                (position_cards, draw_card).run_if(in_state(AppState::Playing)),
+                (position_cards, draw_card.run_if(in_state(GameState::Turn)))
+                    .run_if(in_state(AppState::Playing)),
             )
             .add_plugins(InputManagerPlugin::<DeckAction>::default());
     }
 }
 
 //spawn deck when deck plugin is made
-fn spawn_deck(mut cmd: Commands, textures: Res<TextureAssets>) {
+fn spawn_deck(mut cmd: Commands, textures: Res<TextureAssets>, mut dice: ResMut<Dice>) {
+    let mut cards = Vec::new();
+    for &(kind, count) in DEV_CARDS.iter() {
+        for _ in 0..count {
+            cards.push(kind);
+        }
+    }
+    shuffle(&mut random::default(dice.seed()), &mut cards);
+
     let deck_id = cmd
         .spawn((
             InputManagerBundle::<DeckAction> {
                 action_state: ActionState::default(),
                 input_map: InputMap::new([(KeyCode::Space, DeckAction::Draw)]),
             },
-            Deck { size: 60 },
+            Deck { size: cards.len() },
             SpatialBundle {
                 transform: Transform {
                     translation: Vec3::new(-400., -150., 0.),
@@ -56,49 +102,36 @@ fn spawn_deck(mut cmd: Commands, textures: Res<TextureAssets>) {
         ))
         .id();
 
-    //TODO make this a make_card func
-    for i in 0..60 {
-        let front = cmd
-            .spawn((
-                SpriteBundle {
-                    texture: textures.card_king.clone(),
-                    visibility: Visibility::Hidden,
-                    transform: Transform {
-                        rotation: Quat::from_euler(EulerRot::XYZ, 0., PI, 0.),
-                        ..default()
-                    },
-
-                    ..default()
-                },
-                CardFace { is_front: true },
-            ))
-            .id();
-        let back = cmd
-            .spawn((
-                SpriteBundle {
-                    texture: textures.card_blue.clone(),
-                    ..default()
-                },
-                CardFace { is_front: false },
-            ))
-            .id();
-
-        let card_id = cmd
-            .spawn(CardBundle {
-                card: Card {
-                    back,
-                    front,
-                    face_up: false,
-                },
-                sprite: SpriteBundle { ..default() },
-                ordinal: Ordinal(i),
-            })
-            .id();
-
-        cmd.entity(card_id).push_children(&[front, back]);
+    for (i, &kind) in cards.iter().enumerate() {
+        let face = match kind {
+            DevCard::Knight => textures.knight_card.clone(),
+            DevCard::VictoryPoint => textures.victory_point_card.clone(),
+            DevCard::RoadBuilding => textures.road_building_card.clone(),
+            DevCard::YearOfPlenty => textures.year_of_plenty_card.clone(),
+            DevCard::Monopoly => textures.monopoly_card.clone(),
+        };
+        let card_id = make_card(
+            &mut cmd,
+            face,
+            textures.card_blue.clone(),
+            Color::WHITE,
+            i,
+            false,
+        );
+        cmd.entity(card_id)
+            .insert(DevelopmentCard { kind, bought: None });
         cmd.entity(deck_id).push_children(&[card_id]);
     }
 }
+fn despawn_deck(
+    mut cmd: Commands,
+    q_deck: Query<Entity, With<Deck>>,
+    q_put_away: Query<Entity, (With<DevelopmentCard>, Without<Parent>)>,
+) {
+    for deck in q_deck.iter().chain(q_put_away.iter()) {
+        cmd.entity(deck).despawn_recursive();
+    }
+}
 fn position_cards(
     q_deck: Query<(&Transform, &Deck, &Children)>,
     mut q_cards: Query<(&Ordinal, &Card, &mut Transform), Without<Deck>>,
@@ -113,6 +146,7 @@ fn position_cards(
     }
 }
 
+//buying a development card, it goes into the hand face down
 pub fn draw_card(
     mut cmd: Commands,
     mut query: Query<
@@ -124,19 +158,39 @@ pub fn draw_card(
         ),
         Without<Card>,
     >,
-    mut q_cards: Query<(&Card, &mut Ordinal, &mut Transform)>,
+    mut q_cards: Query<(&mut DevelopmentCard, &mut Ordinal, &mut Transform)>,
     mut hand: Query<(Entity, &mut Hand)>,
-    mut flip_writer: EventWriter<FlipCard>,
+    mut bank: ResMut<Bank>,
+    mut players: ResMut<Players>,
 ) {
     let (action_state, deck_transform, mut deck, children) = query.single_mut();
 
     if action_state.just_pressed(DeckAction::Draw) {
+        if deck.size == 0 {
+            info!("no development cards left");
+            return;
+        }
+        if let Err(e) = players.active().can_afford(Build::DevelopmentCard) {
+            info!("{} {}", players.active().name, e);
+            return;
+        }
         let (entity, mut hand) = hand.single_mut();
         for &child in children.iter() {
-            if let Ok((card, mut ordinal, mut card_transform)) = q_cards.get_mut(child) {
+            if let Ok((mut dev_card, mut ordinal, mut card_transform)) = q_cards.get_mut(child) {
                 if ordinal.0 != deck.size - 1 {
                     continue;
                 }
+                let turn = players.turn;
+                let player = players.active_mut();
+                if let Err(e) = player.pay(Build::DevelopmentCard, &mut bank) {
+                    info!("{} {}", player.name, e);
+                    return;
+                }
+                dev_card.bought = Some((player.id, turn));
+                //victory points count straight away even though nobody else can see them
+                if dev_card.kind == DevCard::VictoryPoint {
+                    player.bonus_cards.push(BonusCard::VictoryPoint);
+                }
                 cmd.entity(child).remove_parent();
 
                 ordinal.0 = hand.size;
@@ -146,10 +200,60 @@ pub fn draw_card(
                 deck.size -= 1;
                 cmd.entity(entity).push_children(&[child]);
                 hand.size += 1;
-                flip_writer.send(FlipCard { card: child });
 
                 return;
             }
         }
     }
 }
+
+//the hand only holds the shown player's development cards, everyone else's are taken out of it
+//and hidden until their owner is shown again
+pub fn sync_dev_cards(
+    mut cmd: Commands,
+    players: Res<Players>,
+    discards: Res<Discards>,
+    stage: Res<State<RollStage>>,
+    mut q_hand: Query<(Entity, &mut Hand, Option<&Children>)>,
+    mut q_dev: Query<(Entity, &DevelopmentCard, Option<&Parent>, &mut Visibility)>,
+    mut q_ordinals: Query<&mut Ordinal>,
+) {
+    let Ok((hand_entity, mut hand, children)) = q_hand.get_single_mut() else {
+        return;
+    };
+    let owner = shown_player(&players, &discards, stage.get());
+
+    let mut removed = Vec::new();
+    let mut returned = Vec::new();
+    for (card, dev_card, parent, mut visibility) in q_dev.iter_mut() {
+        let Some((bought_by, _)) = dev_card.bought else {
+            continue;
+        };
+        match parent.map(|p| p.get()) {
+            Some(p) if p == hand_entity && bought_by != owner => {
+                removed.push(card);
+                *visibility = Visibility::Hidden;
+            },
+            None if bought_by == owner => {
+                returned.push(card);
+                *visibility = Visibility::Inherited;
+            },
+            _ => {},
+        }
+    }
+    if !removed.is_empty() {
+        if let Some(children) = children {
+            remove_from_hand(&mut hand, children, &removed, &mut q_ordinals);
+        }
+        for &card in removed.iter() {
+            cmd.entity(card).remove_parent();
+        }
+    }
+    for card in returned {
+        if let Ok(mut ord) = q_ordinals.get_mut(card) {
+            ord.0 = hand.size;
+        }
+        cmd.entity(hand_entity).push_children(&[card]);
+        hand.size += 1;
+    }
+}
@@ -26,6 +26,19 @@ pub struct Hand {
     pub hovered: Option<Entity>,
 }
 
+#[derive(Event)]
+pub struct CardSelected {
+    pub card: Entity,
+}
+//a card let go of above the hand, whoever owns that kind of card decides what it does
+#[derive(Event)]
+pub struct CardPlayed {
+    pub card: Entity,
+}
+
+//cards dropped higher than this count as dragged out of the hand
+const PLAY_HEIGHT: f32 = 150.;
+
 #[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
 pub enum HandAction {
     Select,
@@ -57,7 +70,10 @@ pub struct HandPlugin;
 impl Plugin for HandPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<HandAction>::default())
+            .add_event::<CardSelected>()
+            .add_event::<CardPlayed>()
             .add_systems(OnEnter(AppState::Playing), spawn_hand)
+            .add_systems(OnExit(AppState::Playing), despawn_hand)
             .add_systems(Update, component_animator_system::<Transform>)
             .add_systems(
                 Update,
@@ -86,6 +102,37 @@ fn spawn_hand(mut commands: Commands) {
             hovered: None,
         });
 }
+fn despawn_hand(mut cmd: Commands, q_hand: Query<Entity, With<Hand>>) {
+    for hand in q_hand.iter() {
+        cmd.entity(hand).despawn_recursive();
+    }
+}
+//take cards out of the hand and close the gaps they leave in the ordinals
+pub fn remove_from_hand(
+    hand: &mut Hand,
+    children: &Children,
+    removed: &[Entity],
+    q_ordinals: &mut Query<&mut Ordinal>,
+) {
+    let mut remaining: Vec<(usize, Entity)> = children
+        .iter()
+        .filter(|&c| !removed.contains(c))
+        .filter_map(|&c| q_ordinals.get(c).ok().map(|o| (o.0, c)))
+        .collect();
+    remaining.sort();
+    for (i, &(_, c)) in remaining.iter().enumerate() {
+        if let Ok(mut ord) = q_ordinals.get_mut(c) {
+            ord.0 = i;
+        }
+    }
+    hand.size = remaining.len();
+    if hand.selected.is_some_and(|s| removed.contains(&s)) {
+        hand.selected = None;
+    }
+    if hand.hovered.is_some_and(|h| removed.contains(&h)) {
+        hand.hovered = None;
+    }
+}
 //whenever hand is updated position cards in hand that are not selected by ord using a tween
 fn position_cards(
     mut cmd: Commands,
@@ -160,6 +207,8 @@ fn select_card(
     mut q_cards: Query<(Entity, &Card, &Transform, &Ordinal)>,
     mut q_camera: Query<(&Camera, &GlobalTransform), With<CardCamera>>,
     mut flip_writer: EventWriter<FlipCard>,
+    mut selected_writer: EventWriter<CardSelected>,
+    mut played_writer: EventWriter<CardPlayed>,
 ) {
     if query.is_empty() {
         return;
@@ -234,6 +283,9 @@ fn select_card(
         }
         if action_state.just_pressed(HandAction::Select) && hand.hovered.is_some() {
             hand.selected = hand.hovered;
+            selected_writer.send(CardSelected {
+                card: hand.selected.unwrap(),
+            });
 
             if let Ok((entity, card, transform, ord)) = q_cards.get(hand.selected.unwrap()) {
                 //straigten the card
@@ -257,6 +309,13 @@ fn select_card(
 
     let select_released = action_state.just_released(HandAction::Select);
     if select_released {
+        if let Some(selected) = hand.selected {
+            if let Ok((entity, _, transform, _)) = q_cards.get(selected) {
+                if transform.translation.y > PLAY_HEIGHT {
+                    played_writer.send(CardPlayed { card: entity });
+                }
+            }
+        }
         hand.selected = None;
     }
 }
@@ -1,10 +1,14 @@
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
 mod board;
 mod camera;
 mod card;
 mod deck;
 mod hand;
 mod loading;
+mod player;
 mod utils;
+mod victory;
 use bevy::prelude::*;
 use board::BoardPlugin;
 use camera::CameraPlugin;
@@ -13,6 +17,11 @@ use deck::{DeckAction, DeckPlugin};
 use hand::HandPlugin;
 use leafwing_input_manager::prelude::*;
 use loading::LoadingPlugin;
+use player::PlayerPlugin;
+use victory::VictoryPlugin;
+
+//hex math and board coordinates for tools that work on boards outside the game
+pub use board::{axial::Axial, edge::EdgeCoord, vertex::VertexCoord};
 
 pub struct GamePlugin;
 
@@ -23,6 +32,8 @@ pub enum AppState {
     Loading,
     // During this State the actual game logic is executed
     Playing,
+    // Someone reached the victory target, the results are shown until a new game starts
+    GameOver,
 }
 
 impl Plugin for GamePlugin {
@@ -31,6 +42,8 @@ impl Plugin for GamePlugin {
             .add_plugin(CameraPlugin)
             .add_plugins((DeckPlugin, HandPlugin, CardPlugin))
             .add_plugins(LoadingPlugin)
-            .add_plugins(BoardPlugin);
+            .add_plugins(PlayerPlugin)
+            .add_plugins(BoardPlugin)
+            .add_plugins(VictoryPlugin);
     }
 }
@@ -2,18 +2,24 @@ use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use bevy_kira_audio::AudioSource;
 
-use crate::AppState;
+use crate::{
+    board::map::{Map, MapLoader},
+    AppState,
+};
 
 pub struct LoadingPlugin;
 
 impl Plugin for LoadingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_loading_state(
-            LoadingState::new(AppState::Loading).continue_to_state(AppState::Playing),
-        )
-        // .add_collection_to_loading_state::<_, FontAssets>(GameState::Loading)
-        // .add_collection_to_loading_state::<_, AudioAssets>(GameState::Loading)
-        .add_collection_to_loading_state::<_, TextureAssets>(AppState::Loading);
+        app.add_asset::<Map>()
+            .init_asset_loader::<MapLoader>()
+            .add_loading_state(
+                LoadingState::new(AppState::Loading).continue_to_state(AppState::Playing),
+            )
+            // .add_collection_to_loading_state::<_, FontAssets>(GameState::Loading)
+            // .add_collection_to_loading_state::<_, AudioAssets>(GameState::Loading)
+            .add_collection_to_loading_state::<_, TextureAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, MapAssets>(AppState::Loading);
     }
 }
 
@@ -29,16 +35,34 @@ impl Plugin for LoadingPlugin {
 //     pub flying: Handle<AudioSource>,
 // }
 
+//the board that gets played, edit the file to change it without rebuilding
+#[derive(AssetCollection, Resource)]
+pub struct MapAssets {
+    #[asset(path = "maps/board.map.ron")]
+    pub board: Handle<Map>,
+}
+
 #[derive(AssetCollection, Resource)]
 pub struct TextureAssets {
     #[asset(path = "faces/card_red.png")]
     pub card_red: Handle<Image>,
     #[asset(path = "faces/card_blue.png")]
     pub card_blue: Handle<Image>,
-    #[asset(path = "faces/cardSpadesK.png")]
-    pub card_king: Handle<Image>,
     #[asset(path = "faces/cardSpadesA.png")]
     pub card_ace: Handle<Image>,
+    #[asset(path = "faces/card_blank.png")]
+    pub card_blank: Handle<Image>,
+    //development card faces, placeholders borrowed from the playing cards until they get art
+    #[asset(path = "faces/cardSpadesJ.png")]
+    pub knight_card: Handle<Image>,
+    #[asset(path = "faces/cardHeartsA.png")]
+    pub victory_point_card: Handle<Image>,
+    #[asset(path = "faces/cardClubs2.png")]
+    pub road_building_card: Handle<Image>,
+    #[asset(path = "faces/cardDiamondsQ.png")]
+    pub year_of_plenty_card: Handle<Image>,
+    #[asset(path = "faces/cardClubsK.png")]
+    pub monopoly_card: Handle<Image>,
 
     #[asset(path = "wood_tile.png")]
     pub wood_tile: Handle<Image>,
@@ -0,0 +1,200 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::board::{
+    bank::Bank,
+    cost::{Build, BuildError},
+    hex,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlayerId(pub usize);
+
+//pieces a player has left to put on the board
+#[derive(Debug, Clone, Copy)]
+pub struct Supply {
+    pub settlements: u32,
+    pub cities: u32,
+    pub roads: u32,
+}
+impl Default for Supply {
+    fn default() -> Self {
+        Supply {
+            settlements: 5,
+            cities: 4,
+            roads: 15,
+        }
+    }
+}
+
+//cards that are worth points on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BonusCard {
+    LongestRoad,
+    LargestArmy,
+    VictoryPoint,
+}
+impl BonusCard {
+    pub fn points(&self) -> u32 {
+        match self {
+            BonusCard::LongestRoad | BonusCard::LargestArmy => 2,
+            BonusCard::VictoryPoint => 1,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Player {
+    pub id: PlayerId,
+    pub name: String,
+    pub color: Color,
+    pub resources: HashMap<hex::Resource, u32>,
+    pub victory_points: u32,
+    pub bonus_cards: Vec<BonusCard>,
+    //knights played so far
+    pub army: u32,
+    pub supply: Supply,
+}
+impl Player {
+    pub fn new(id: PlayerId, name: String, color: Color) -> Self {
+        Player {
+            id,
+            name,
+            color,
+            resources: HashMap::new(),
+            victory_points: 0,
+            bonus_cards: Vec::new(),
+            army: 0,
+            supply: Supply::default(),
+        }
+    }
+    pub fn card_count(&self) -> u32 {
+        self.resources.values().sum()
+    }
+    pub fn add(&mut self, resource: hex::Resource, amount: u32) {
+        *self.resources.entry(resource).or_default() += amount;
+    }
+    pub fn has_all(&self, cost: &[(hex::Resource, u32)]) -> bool {
+        cost.iter()
+            .all(|(resource, amount)| self.resources.get(resource).unwrap_or(&0) >= amount)
+    }
+    pub fn take_all(&mut self, cost: &[(hex::Resource, u32)]) {
+        for (resource, amount) in cost {
+            let held = self.resources.entry(*resource).or_default();
+            *held = held.saturating_sub(*amount);
+        }
+    }
+
+    pub fn has_piece(&self, build: Build) -> Result<(), BuildError> {
+        let left = match build {
+            Build::Road => self.supply.roads,
+            Build::Settlement => self.supply.settlements,
+            Build::City => self.supply.cities,
+            //the deck running out is the deck's problem
+            Build::DevelopmentCard => 1,
+        };
+        if left == 0 {
+            return Err(BuildError::OutOfPieces(build));
+        }
+        Ok(())
+    }
+    pub fn can_afford(&self, build: Build) -> Result<(), BuildError> {
+        self.has_piece(build)?;
+        let missing: Vec<(hex::Resource, u32)> = build
+            .cost()
+            .iter()
+            .filter_map(|&(resource, amount)| {
+                let held = *self.resources.get(&resource).unwrap_or(&0);
+                (held < amount).then_some((resource, amount - held))
+            })
+            .collect();
+        if !missing.is_empty() {
+            return Err(BuildError::CannotAfford { build, missing });
+        }
+        Ok(())
+    }
+    /// Pay for a build into the bank and take the piece out of the supply
+    pub fn pay(&mut self, build: Build, bank: &mut Bank) -> Result<(), BuildError> {
+        self.can_afford(build)?;
+        self.take_all(build.cost());
+        bank.put_all(build.cost());
+        self.take_piece(build)
+    }
+    //for builds that are free, like during setup
+    pub fn take_piece(&mut self, build: Build) -> Result<(), BuildError> {
+        self.has_piece(build)?;
+        match build {
+            Build::Road => self.supply.roads -= 1,
+            Build::Settlement => self.supply.settlements -= 1,
+            //the settlement under a city goes back to the supply
+            Build::City => {
+                self.supply.cities -= 1;
+                self.supply.settlements += 1;
+            },
+            Build::DevelopmentCard => {},
+        }
+        Ok(())
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct Players {
+    pub list: Vec<Player>,
+    pub active: PlayerId,
+    //turns ended since the game left setup
+    pub turn: u32,
+}
+impl Players {
+    pub fn new(count: usize) -> Self {
+        let colors = [
+            Color::rgb(0.85, 0.2, 0.2),
+            Color::rgb(0.2, 0.4, 0.85),
+            Color::rgb(0.95, 0.6, 0.1),
+            Color::rgb(0.95, 0.95, 0.95),
+        ];
+        let list = (0..count)
+            .map(|i| {
+                Player::new(
+                    PlayerId(i),
+                    format!("Player {}", i + 1),
+                    colors[i % colors.len()],
+                )
+            })
+            .collect();
+        Players {
+            list,
+            active: PlayerId(0),
+            turn: 0,
+        }
+    }
+    pub fn count(&self) -> usize {
+        self.list.len()
+    }
+    pub fn ids(&self) -> impl Iterator<Item = PlayerId> + '_ {
+        self.list.iter().map(|p| p.id)
+    }
+    pub fn get(&self, id: PlayerId) -> &Player {
+        &self.list[id.0]
+    }
+    pub fn get_mut(&mut self, id: PlayerId) -> &mut Player {
+        &mut self.list[id.0]
+    }
+    pub fn active(&self) -> &Player {
+        self.get(self.active)
+    }
+    pub fn active_mut(&mut self) -> &mut Player {
+        let active = self.active;
+        self.get_mut(active)
+    }
+    pub fn next(&mut self) {
+        self.active = PlayerId((self.active.0 + 1) % self.count());
+        self.turn += 1;
+    }
+}
+
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Players::new(4));
+    }
+}
@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+
+use crate::{
+    board::{
+        bank::Bank,
+        board_data::BoardData,
+        discard::Discards,
+        hex::BuildType,
+        log::GameLog,
+        map::BoardSeed,
+        progress::CardStage,
+        roll::{Dice, TurnEnded},
+        setup::Setup,
+        GameState, RollStage,
+    },
+    player::{Player, PlayerId, Players},
+    AppState,
+};
+
+//points needed to win, checked when a player ends their turn
+#[derive(Resource)]
+pub struct VictoryTarget(pub u32);
+impl Default for VictoryTarget {
+    fn default() -> Self {
+        VictoryTarget(10)
+    }
+}
+
+#[derive(Resource)]
+pub struct Winner(pub PlayerId);
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Score {
+    pub settlements: u32,
+    pub cities: u32,
+    pub bonus: u32,
+}
+impl Score {
+    pub fn of(board: &BoardData, player: &Player) -> Self {
+        let mut score = Score::default();
+        for v in board.vertices.values() {
+            if v.owner != Some(player.id) {
+                continue;
+            }
+            match v.build_type {
+                BuildType::Settlement => score.settlements += 1,
+                BuildType::City => score.cities += 1,
+                BuildType::None => {},
+            }
+        }
+        score.bonus = player.bonus_cards.iter().map(|c| c.points()).sum();
+        score
+    }
+    pub fn total(&self) -> u32 {
+        self.settlements + self.cities * 2 + self.bonus
+    }
+}
+
+#[derive(Component)]
+struct ResultsScreen;
+#[derive(Component)]
+struct PlayAgainButton;
+
+pub struct VictoryPlugin;
+
+impl Plugin for VictoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VictoryTarget>()
+            .add_systems(
+                Update,
+                (tally_victory_points, check_victory)
+                    .chain()
+                    .run_if(in_state(AppState::Playing)),
+            )
+            .add_systems(OnEnter(AppState::GameOver), spawn_results)
+            .add_systems(Update, play_again.run_if(in_state(AppState::GameOver)))
+            .add_systems(OnExit(AppState::GameOver), despawn_results);
+    }
+}
+
+//keep the cached points on each player in step with the board
+fn tally_victory_points(board: Res<BoardData>, mut players: ResMut<Players>) {
+    if !board.is_changed() && !players.is_changed() {
+        return;
+    }
+    //only write back what moved so this doesn't keep marking players as changed
+    let totals: Vec<(PlayerId, u32)> = players
+        .list
+        .iter()
+        .map(|p| (p.id, Score::of(&board, p).total()))
+        .filter(|&(id, total)| players.get(id).victory_points != total)
+        .collect();
+    for (id, total) in totals {
+        players.get_mut(id).victory_points = total;
+    }
+}
+
+//a player can only win on their own turn, so only look at whoever just ended it
+fn check_victory(
+    mut cmd: Commands,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    target: Res<VictoryTarget>,
+    mut ended: EventReader<TurnEnded>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for e in ended.iter() {
+        let player = players.get(e.player);
+        if Score::of(&board, player).total() >= target.0 {
+            info!("{} wins", player.name);
+            cmd.insert_resource(Winner(e.player));
+            next_state.set(AppState::GameOver);
+            return;
+        }
+    }
+}
+
+fn spawn_results(
+    mut cmd: Commands,
+    board: Res<BoardData>,
+    players: Res<Players>,
+    winner: Res<Winner>,
+) {
+    cmd.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.),
+                ..default()
+            },
+            background_color: Color::rgba(0., 0., 0., 0.8).into(),
+            ..default()
+        },
+        ResultsScreen,
+    ))
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            format!("{} wins!", players.get(winner.0).name),
+            TextStyle {
+                font_size: 48.,
+                color: players.get(winner.0).color,
+                ..default()
+            },
+        ));
+        for player in players.list.iter() {
+            let score = Score::of(&board, player);
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "{}: {} settlements, {} cities, {} bonus = {} points",
+                    player.name,
+                    score.settlements,
+                    score.cities,
+                    score.bonus,
+                    score.total()
+                ),
+                TextStyle {
+                    font_size: 24.,
+                    color: player.color,
+                    ..default()
+                },
+            ));
+        }
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::all(Val::Px(12.)),
+                        margin: UiRect::top(Val::Px(24.)),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.25, 0.25, 0.25).into(),
+                    ..default()
+                },
+                PlayAgainButton,
+            ))
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(
+                    "Play again",
+                    TextStyle {
+                        font_size: 28.,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            });
+    });
+}
+
+//start over on a freshly dealt board with the same number of players
+fn play_again(
+    mut cmd: Commands,
+    mut dice: ResMut<Dice>,
+    players: Res<Players>,
+    q_button: Query<&Interaction, (Changed<Interaction>, With<PlayAgainButton>)>,
+    mut next_app: ResMut<NextState<AppState>>,
+    mut next_game: ResMut<NextState<GameState>>,
+    mut next_stage: ResMut<NextState<RollStage>>,
+    mut next_card_stage: ResMut<NextState<CardStage>>,
+) {
+    for interaction in q_button.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        cmd.insert_resource(BoardSeed(dice.seed()));
+        cmd.insert_resource(Players::new(players.count()));
+        cmd.insert_resource(Setup::default());
+        cmd.insert_resource(Bank::default());
+        cmd.insert_resource(Discards::default());
+        cmd.insert_resource(GameLog::default());
+        cmd.remove_resource::<Winner>();
+        next_game.set(GameState::Setup);
+        next_stage.set(RollStage::Dice);
+        next_card_stage.set(CardStage::Idle);
+        next_app.set(AppState::Playing);
+        return;
+    }
+}
+
+fn despawn_results(mut cmd: Commands, q: Query<Entity, With<ResultsScreen>>) {
+    for entity in q.iter() {
+        cmd.entity(entity).despawn_recursive();
+    }
+}